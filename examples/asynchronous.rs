@@ -29,7 +29,7 @@ async fn main(_spawner: Spawner) {
             let temp = t.wait_temp().await?;
             info!("Temperature {}", temp);
         }
-        Ok(())
+        Ok(t)
     })
     .await
     .unwrap();
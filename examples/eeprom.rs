@@ -0,0 +1,37 @@
+#![no_std]
+#![no_main]
+#![feature(type_alias_impl_trait)]
+
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_nrf::{interrupt, twim::Twim};
+use embassy_time::Delay;
+use tmp117::{register::Average, Tmp117};
+use {defmt_rtt as _, embassy_nrf as _, panic_probe as _};
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_nrf::init(Default::default());
+    info!("Start");
+
+    let irq = interrupt::take!(SPIM0_SPIS0_TWIM0_TWIS0_SPI0_TWI0);
+    let twi = Twim::new(p.TWISPI0, irq, p.P1_10, p.P1_11, Default::default());
+
+    let mut tmp = Tmp117::<0x49, _, _>::new(twi);
+    let mut delay = Delay;
+
+    // Calibrate against a reference and program the offset so it survives a reset
+    let reference = 25.0;
+    let measured = tmp.oneshot(Average::Avg64).unwrap();
+    let offset = tmp.get_offset().unwrap() + reference - measured.into_inner();
+    info!("Programming offset {}", offset);
+    tmp.set_offset(offset).unwrap();
+    tmp.save_config_to_eeprom().unwrap();
+
+    // The soft reset reloads the registers from the EEPROM
+    tmp.reset(&mut delay).unwrap();
+    info!("Offset after reset {}", tmp.get_offset().unwrap());
+    info!("Temperature {}", tmp.oneshot(Average::Avg64).unwrap());
+
+    cortex_m::asm::bkpt();
+}
@@ -0,0 +1,48 @@
+//! Helper for the four TMP117s that can share a bus, one per ADD0 strap
+use core::cell::RefCell;
+
+use embedded_hal::i2c::{I2c, SevenBitAddress};
+use embedded_hal_bus::i2c::RefCellDevice;
+
+use crate::{register::Average, Error, I2cAddress, Tmp117, RUNTIME_ADDRESS};
+
+/// Driver of a TMP117 of the array, sharing the bus with the others
+pub type SharedTmp117<'a, T, E> = Tmp117<RUNTIME_ADDRESS, RefCellDevice<'a, T>, E>;
+
+/// Four TMP117s on the same bus, one for each [I2cAddress].
+/// The bus is shared with a [RefCellDevice], so the array must be used from a single context
+pub struct Tmp117Array<'a, T, E> {
+    tmps: [SharedTmp117<'a, T, E>; 4],
+}
+
+impl<'a, T, E> Tmp117Array<'a, T, E>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+{
+    /// Create the drivers of the four addresses sharing the bus
+    pub fn new(bus: &'a RefCell<T>) -> Self {
+        Self {
+            tmps: I2cAddress::ALL.map(|a| Tmp117::new_with_address(RefCellDevice::new(bus), a)),
+        }
+    }
+
+    /// Access the driver of a single device
+    pub fn get_mut(&mut self, address: I2cAddress) -> &mut SharedTmp117<'a, T, E> {
+        &mut self.tmps[(address as u8 - I2cAddress::Gnd as u8) as usize]
+    }
+
+    /// Do a oneshot without averaging on the four devices and returns the temperatures in celsius,
+    /// in the order of [I2cAddress::ALL]. Every conversion is started before waiting, so they run at the same time.
+    /// A device failing, e.g. not populated on the board, doesn't prevent reading the others
+    pub fn read_all(&mut self) -> [Result<f32, Error<E>>; 4] {
+        let started: [Result<(), Error<E>>; 4] =
+            core::array::from_fn(|i| self.tmps[i].set_oneshot(Average::NoAverage, None));
+        core::array::from_fn(|i| {
+            started[i]?;
+            let tmp = &mut self.tmps[i];
+            tmp.wait_for_data()?;
+            Ok(tmp.read_temp_raw()?.0)
+        })
+    }
+}
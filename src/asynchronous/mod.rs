@@ -6,9 +6,19 @@ use device_register_async::{EditRegister, ReadRegister, WriteRegister};
 use embedded_hal::{digital::ErrorType, i2c::SevenBitAddress};
 use embedded_hal_async::{delay::DelayNs, digital::Wait, i2c::I2c};
 
-use crate::{register::*, Alert, ContinuousConfig, Error, Id, CELCIUS_CONVERSION};
+use crate::{
+    celsius_to_counts, celsius_to_fahrenheit, celsius_to_kelvin, celsius_to_raw, counts_to_celsius,
+    decode_config, error::ErrorLL, fahrenheit_centi_from_counts, register::*, scale_counts,
+    user_eeprom_address, Alert, Celsius, Clock, ConfigSnapshot, ContinuousConfig, Error,
+    FromRawTemperature, I2cAddress, Id, Measurement, MeasurementFrame, NoDelay, NoiseStats,
+    OneShotFilter, SessionAccumulator, SessionStats, Settings, TempStatus, CONFIG_RESET_BIT,
+    DATA_TIMEOUT_CYCLES, EEPROM_WRITE_TIMEOUT_MS, FIRST_CONVERSION_TIMEOUT_US, POLL_INTERVAL_US,
+    POWER_UP_TEMPERATURE, POWER_UP_TIMEOUT_US, RESET_DELAY_MS, RUNTIME_ADDRESS,
+};
 
 use self::tmp117_ll::Tmp117LL;
+pub mod monitor;
+pub mod thermostat;
 pub mod tmp117_ll;
 
 /// Dummy type for wait pin, should never be
@@ -58,11 +68,39 @@ impl<P> AlertPin<P> {
     }
 }
 
+/// How the driver waits on the alert pin
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum PinWait {
+    /// Wait for the pin to be at its active level, returns right away if it is already active.
+    /// A flag left set, e.g. by a conversion that wasn't read, wakes the driver before the new conversion.
+    /// Only [wait_temp_checked](ContinuousHandler::wait_temp_checked) reads the configuration after the pin to confirm the flag and the mode.
+    /// The [oneshots](Tmp117::oneshot) and the other continuous waits, e.g. [wait_temp](ContinuousHandler::wait_temp),
+    /// read the temperature right away, so a stale active level returns the previous temperature without any check,
+    /// use [Edge](PinWait::Edge) if that matters
+    #[default]
+    Level,
+
+    /// Wait for the transition to the active level, a pin still active from a previous flag doesn't wake the driver.
+    /// A transition happening before the wait starts is missed, the driver then waits for the next one,
+    /// so a [oneshot](Tmp117::oneshot) only completes if its conversion ends after the wait started
+    Edge,
+}
+
 /// The TMP117 driver. Note that the alert pin is optional, but it is recommended to pass it if possible
 /// If the alert pin is `None`, the driver will poll the config register instead of waiting for the pin.
-pub struct Tmp117<const ADDR: u8, T, E, P> {
+/// `O` and `K` are the types of the [read observer](Tmp117::set_read_observer) and of the [calibration](Tmp117::set_calibration),
+/// function pointers by default, see [with_read_observer](Tmp117::with_read_observer) to use a capturing closure
+pub struct Tmp117<const ADDR: u8, T, E, P, O = fn(f32), K = fn(f32) -> f32> {
     tmp_ll: Tmp117LL<ADDR, T, E>,
     alert: Option<AlertPin<P>>,
+    observer: Option<O>,
+    calibration: Option<K>,
+    config: Option<Configuration>,
+    use_cache: bool,
+    session: SessionAccumulator,
+    oneshot_polarity: Polarity,
+    pin_wait: PinWait,
 }
 
 impl<const ADDR: u8, T, E> Tmp117<ADDR, T, E, DummyWait>
@@ -70,22 +108,78 @@ where
     T: I2c<SevenBitAddress, Error = E>,
     E: embedded_hal::i2c::Error + Copy,
 {
-    /// Create a new tmp117 from a i2c bus
+    /// Create a new tmp117 from a i2c bus at the `ADDR` of the driver, written as `{ I2cAddress::Gnd as u8 }`
+    /// so only the addresses of the device are used. See [new_with_address](Tmp117::new_with_address) to pass the
+    /// [I2cAddress] directly
     /// # Warning
     /// You should use the `new_with_alert` function instead if possible
     /// It seems the tmp117 doesn't always set the data ready flag, so you should add a timeout when using `oneshot` wihout an alert pin.
     /// See [this](https://e2e.ti.com/support/sensors-group/sensors/f/sensors-forum/909104/tmp117-polling-the-data-ready-flag-seems-to-clear-it-inadvertently-when-using-1-shot-mode)
     /// and [this](https://e2e.ti.com/support/sensors-group/sensors/f/sensors-forum/1019457/tmp117-data_ready-flag-cleared-incorrectly-if-data-becomes-ready-during-read-of-configuration-register)
     /// for more information.
-    /// TODO: Pass and use delay instead of polling to fix this
+    /// Use [oneshot_with_timeout](Tmp117::oneshot_with_timeout) to wait with a delay instead of polling.
     pub fn new(i2c: T) -> Tmp117<ADDR, T, E, DummyWait> {
         Tmp117::<ADDR, T, E, DummyWait> {
             tmp_ll: Tmp117LL::new(i2c),
             alert: None,
+            observer: None,
+            calibration: None,
+            config: None,
+            use_cache: false,
+            session: SessionAccumulator::default(),
+            oneshot_polarity: Polarity::ActiveLow,
+            pin_wait: PinWait::Level,
+        }
+    }
+
+    /// Create a new tmp117 from a i2c bus for a device already configured, e.g. by a bootloader or a previous firmware stage,
+    /// and read back its [settings](Tmp117::read_settings). Nothing is written to the device.
+    /// Note that reading the configuration clears the data ready and alert flags
+    pub async fn attach(i2c: T) -> Result<(Tmp117<ADDR, T, E, DummyWait>, Settings), Error<E>> {
+        let mut tmp = Self::new(i2c);
+        let settings = tmp.read_settings().await?;
+        Ok((tmp, settings))
+    }
+}
+
+impl<T, E> Tmp117<RUNTIME_ADDRESS, T, E, DummyWait>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+{
+    /// Create a new tmp117 from a i2c bus with an address known at runtime, e.g. from a board config.
+    /// The `ADDR` of the driver is the [RUNTIME_ADDRESS] marker, so drivers with different addresses share the same type.
+    /// The same warning as [new](Tmp117::new) applies
+    pub fn new_with_address(
+        i2c: T,
+        address: I2cAddress,
+    ) -> Tmp117<RUNTIME_ADDRESS, T, E, DummyWait> {
+        Tmp117::<RUNTIME_ADDRESS, T, E, DummyWait> {
+            tmp_ll: Tmp117LL::new_with_address(i2c, address),
+            alert: None,
+            observer: None,
+            calibration: None,
+            config: None,
+            use_cache: false,
+            session: SessionAccumulator::default(),
+            oneshot_polarity: Polarity::ActiveLow,
+            pin_wait: PinWait::Level,
         }
     }
 }
 
+impl<T, E, P> Tmp117<RUNTIME_ADDRESS, T, E, P>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: Wait,
+{
+    /// Create a new tmp117 from a i2c bus and alert pin with an address known at runtime, see [new_with_address](Tmp117::new_with_address)
+    pub fn new_alert_with_address(i2c: T, alert: P, address: I2cAddress) -> Self {
+        Self::new_from_ll(Tmp117LL::new_with_address(i2c, address), alert)
+    }
+}
+
 impl<const ADDR: u8, T, E, P> Tmp117<ADDR, T, E, P>
 where
     T: I2c<SevenBitAddress, Error = E>,
@@ -97,6 +191,13 @@ where
         Self {
             tmp_ll: Tmp117LL::new(i2c),
             alert: Some(AlertPin::Unkown(alert)),
+            observer: None,
+            calibration: None,
+            config: None,
+            use_cache: false,
+            session: SessionAccumulator::default(),
+            oneshot_polarity: Polarity::ActiveLow,
+            pin_wait: PinWait::Level,
         }
     }
 
@@ -105,7 +206,277 @@ where
         Self {
             tmp_ll,
             alert: Some(AlertPin::Unkown(alert)),
+            observer: None,
+            calibration: None,
+            config: None,
+            use_cache: false,
+            session: SessionAccumulator::default(),
+            oneshot_polarity: Polarity::ActiveLow,
+            pin_wait: PinWait::Level,
+        }
+    }
+}
+
+impl<const ADDR: u8, T, E, P, O, K> Tmp117<ADDR, T, E, P, O, K>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: Wait,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    /// Destroys the driver and returns the i2c bus and the alert pin, if any, e.g. to hand them to other drivers.
+    /// The device is left in its current mode
+    pub fn release(self) -> (T, Option<P>) {
+        (self.tmp_ll.release(), self.alert.map(AlertPin::unwrap))
+    }
+
+    /// Borrow the i2c bus, e.g. for a shared bus
+    pub fn inner(&self) -> &T {
+        self.tmp_ll.inner()
+    }
+
+    /// Mutably borrow the i2c bus, e.g. to probe the bus or do a general call without releasing the driver.
+    /// The caller must not leave the registers of the device in a state the driver doesn't expect,
+    /// the [cached configuration](Self::cached_config) is not updated by writes done through the bus
+    pub fn inner_mut(&mut self) -> &mut T {
+        self.tmp_ll.inner_mut()
+    }
+
+    /// Mutably borrow the low level driver to read, write or edit any typed register, e.g. the [HighLimit] directly.
+    /// Writing the configuration through it desyncs the state kept by the driver: the [cached configuration](Self::cached_config),
+    /// used for the polarity of the alert pin and the [use of the cache](Self::set_use_cached_config), and the mode of the alert pin
+    pub fn low_level(&mut self) -> &mut Tmp117LL<ADDR, T, E> {
+        &mut self.tmp_ll
+    }
+
+    /// Set a function called with every temperature successfully read in celsius by
+    /// [oneshot](Tmp117::oneshot), [read_temp](ContinuousHandler::read_temp) and [wait_temp](ContinuousHandler::wait_temp).
+    /// Useful to tee the readings to a logger or telemetry sink without changing the call sites.
+    /// The observer replaces the previous one of the same type, use [with_read_observer](Self::with_read_observer)
+    /// to set a capturing closure
+    pub fn set_read_observer(&mut self, f: O) {
+        self.observer = Some(f);
+    }
+
+    /// Same as [set_read_observer](Self::set_read_observer) but accepts any closure, e.g. one capturing a counter or a channel.
+    /// The type of the observer is part of the type of the driver, so the driver is consumed and returned with the new type
+    pub fn with_read_observer<O2>(self, f: O2) -> Tmp117<ADDR, T, E, P, O2, K>
+    where
+        O2: FnMut(f32),
+    {
+        Tmp117 {
+            tmp_ll: self.tmp_ll,
+            alert: self.alert,
+            observer: Some(f),
+            calibration: self.calibration,
+            config: self.config,
+            use_cache: self.use_cache,
+            session: self.session,
+            oneshot_polarity: self.oneshot_polarity,
+            pin_wait: self.pin_wait,
+        }
+    }
+
+    /// Remove the read observer, if any
+    pub fn clear_read_observer(&mut self) {
+        self.observer = None;
+    }
+
+    /// Set a calibration function applied to every temperature read in celsius before it is returned,
+    /// e.g. a 2 or 3 points polynomial correction. It is applied on top of the hardware
+    /// [TemperatureOffset] since the device already adds it to the temperature register.
+    /// The [read observer](Self::set_read_observer) receives the calibrated value.
+    /// Use [with_calibration](Self::with_calibration) to set a capturing closure, e.g. with coefficients loaded at runtime
+    pub fn set_calibration(&mut self, f: K) {
+        self.calibration = Some(f);
+    }
+
+    /// Same as [set_calibration](Self::set_calibration) but accepts any closure.
+    /// The type of the calibration is part of the type of the driver, so the driver is consumed and returned with the new type
+    pub fn with_calibration<K2>(self, f: K2) -> Tmp117<ADDR, T, E, P, O, K2>
+    where
+        K2: FnMut(f32) -> f32,
+    {
+        Tmp117 {
+            tmp_ll: self.tmp_ll,
+            alert: self.alert,
+            observer: self.observer,
+            calibration: Some(f),
+            config: self.config,
+            use_cache: self.use_cache,
+            session: self.session,
+            oneshot_polarity: self.oneshot_polarity,
+            pin_wait: self.pin_wait,
+        }
+    }
+
+    /// Remove the calibration function, the temperatures are returned as read
+    pub fn clear_calibration(&mut self) {
+        self.calibration = None;
+    }
+
+    /// Set the polarity of the alert pin used by the [oneshot](Self::oneshot), [ActiveLow](Polarity::ActiveLow) by default.
+    /// The continuous mode uses the [polarity of its config](ContinuousConfig::polarity)
+    pub fn set_oneshot_polarity(&mut self, polarity: Polarity) {
+        self.oneshot_polarity = polarity;
+    }
+
+    /// Set how the driver waits on the alert pin, [Level](PinWait::Level) by default
+    pub fn set_pin_wait(&mut self, pin_wait: PinWait) {
+        self.pin_wait = pin_wait;
+    }
+
+    /// Returns the last configuration written by the driver, without any bus transaction.
+    /// This reflects what the driver wrote, not necessarily the live state of the device:
+    /// the alert and data ready flags are the ones read before the write and won't be current.
+    /// Returns `None` if the driver didn't write the configuration yet
+    pub fn cached_config(&self) -> Option<Configuration> {
+        self.config
+    }
+
+    /// Trust the [cached configuration](Self::cached_config) instead of reading the device, disabled by default.
+    /// The read-modify-write of every setter and [transaction](Self::config_transaction) starts from the cache,
+    /// saving the read of the configuration, and the mode changes of [set_shutdown](Self::set_shutdown) and the continuous mode
+    /// are skipped entirely when the device is already configured as requested.
+    /// The cache isn't used after a oneshot since the device goes back to shutdown by itself, and it is invalidated by a [reset](Self::reset).
+    /// It is never used with an alert pin, the read of the configuration is what clears a data ready flag left set
+    /// by a previous conversion, without it the pin would stay active and the next wait would return the previous temperature.
+    ///
+    /// Only enable it if nothing else writes the configuration, the cache would not reflect the device anymore
+    pub fn set_use_cached_config(&mut self, use_cache: bool) {
+        self.use_cache = use_cache;
+    }
+
+    /// Returns the cached configuration if it can be used instead of reading the device
+    fn trusted_config(&self) -> Option<Configuration> {
+        // The read of the configuration clears a stale data ready flag keeping the alert pin active
+        self.config.filter(|c| {
+            self.use_cache && self.alert.is_none() && c.mode() != ConversionMode::OneShot
+        })
+    }
+
+    /// Same as [edit_config](Self::edit_config) but does nothing if the [cache is used](Self::set_use_cached_config)
+    /// and the edit doesn't change the cached configuration
+    async fn edit_config_skip<F>(&mut self, f: F) -> Result<(), Error<E>>
+    where
+        F: Fn(&mut Configuration),
+    {
+        if let Some(cached) = self.trusted_config() {
+            let mut edited = cached;
+            f(&mut edited);
+            if edited == cached {
+                return Ok(());
+            }
+        }
+        self.edit_config(f).await
+    }
+
+    /// Apply several changes to the configuration with a single write, e.g. the mode, average and conversion at once
+    /// instead of a read-modify-write for each setter. The configuration is read once before the changes,
+    /// unless the [cache is used](Self::set_use_cached_config).
+    /// Note that reading the configuration clears the data ready and alert flags
+    pub async fn config_transaction<F>(&mut self, f: F) -> Result<(), Error<E>>
+    where
+        F: FnOnce(&mut Configuration),
+    {
+        self.edit_config(f).await
+    }
+
+    /// Read the conversion mode of the device. Returns [Error::InvalidMode] if the mode bits hold the reserved value.
+    /// Note that reading the configuration clears the data ready and alert flags
+    pub async fn current_mode(&mut self) -> Result<ConversionMode, Error<E>> {
+        let raw = self
+            .tmp_ll
+            .read_raw(RegisterId::Configuration.address())
+            .await?;
+        let config = decode_config(raw)?;
+        Ok(config.mode())
+    }
+
+    /// Read and decode the configuration, returns [Error::InvalidMode] if the mode bits hold the reserved value
+    /// instead of the [Error::InvalidData] of the low level driver
+    async fn read_config(&mut self) -> Result<Configuration, Error<E>> {
+        let raw = self
+            .tmp_ll
+            .read_raw(RegisterId::Configuration.address())
+            .await?;
+        decode_config(raw)
+    }
+
+    /// Read-modify-write the configuration and cache the written value, the read is skipped if the cache is trusted
+    async fn edit_config<F>(&mut self, f: F) -> Result<(), Error<E>>
+    where
+        F: FnOnce(&mut Configuration),
+    {
+        let mut config = match self.trusted_config() {
+            Some(config) => config,
+            None => self.read_config().await?,
+        };
+        f(&mut config);
+        self.write_config(config).await
+    }
+
+    /// Write the whole configuration and cache it
+    async fn write_config(&mut self, config: Configuration) -> Result<(), Error<E>> {
+        self.tmp_ll
+            .write_raw(RegisterId::Configuration.address(), config.into())
+            .await?;
+        self.config = Some(config);
+        Ok(())
+    }
+
+    /// Same as [edit_config_skip](Self::edit_config_skip), but when `complete` the edit sets every field of the configuration
+    /// except the function of the alert pin, which is kept from the last write of the driver, so it is written directly
+    /// without reading the device first. Falls back to the read-modify-write if the driver didn't write the configuration yet
+    async fn edit_config_complete<F>(&mut self, f: F, complete: bool) -> Result<(), Error<E>>
+    where
+        F: Fn(&mut Configuration),
+    {
+        let direct = complete && self.trusted_config().is_none();
+        if let Some(mut config) = self.config.filter(|_| direct) {
+            // The flags are read only, every other writable bit is set by the edit
+            f(&mut config);
+            return self.write_config(config).await;
         }
+        self.edit_config_skip(f).await
+    }
+
+    /// Read the configuration register once and decode all its fields.
+    /// Returns [Error::InvalidMode] if the conversion mode bits hold the reserved value.
+    ///
+    /// Reading the configuration clears the data ready and alert flags: calling this in a poll loop
+    /// while a [oneshot](Self::oneshot) or a continuous conversion completes can make the driver miss the data ready flag
+    pub async fn get_config(&mut self) -> Result<ConfigSnapshot, Error<E>> {
+        let raw = self
+            .tmp_ll
+            .read_raw(RegisterId::Configuration.address())
+            .await?;
+        let config = decode_config(raw)?;
+        Ok(config.into())
+    }
+
+    /// Read the current settings of the device. Returns [Error::InvalidMode] if the conversion mode bits hold the reserved value.
+    /// Note that reading the configuration clears the data ready and alert flags
+    pub async fn read_settings(&mut self) -> Result<Settings, Error<E>> {
+        let raw = self
+            .tmp_ll
+            .read_raw(RegisterId::Configuration.address())
+            .await?;
+        let config = decode_config(raw)?;
+        let high: HighLimit = self.tmp_ll.read().await?;
+        let low: LowLimit = self.tmp_ll.read().await?;
+        let offset: TemperatureOffset = self.tmp_ll.read().await?;
+        Ok(Settings {
+            mode: config.mode(),
+            average: config.average(),
+            conversion: config.conversion(),
+            polarity: config.polarity(),
+            trigger_mode: config.trigger_mode(),
+            high: counts_to_celsius(u16::from(high)),
+            low: counts_to_celsius(u16::from(low)),
+            offset: counts_to_celsius(u16::from(offset)),
+        })
     }
 
     /// Returns the ID of the device
@@ -117,34 +488,362 @@ where
         })
     }
 
+    /// Check that the device answering at the address is a TMP117, returns its [Id] if it is.
+    /// Returns [Error::WrongDevice] if the device ID isn't 0x117, catching a wrong chip or address at startup
+    pub async fn init(&mut self) -> Result<Id, Error<E>> {
+        let id = self.id().await?;
+        if !id.is_tmp117() {
+            return Err(Error::WrongDevice { found: id.device });
+        }
+        Ok(id)
+    }
+
     async fn wait_eeprom(&mut self) -> Result<(), Error<E>> {
-        let mut configuration: Configuration = self.tmp_ll.read().await?;
+        let mut configuration = self.read_config().await?;
         while configuration.eeprom_busy() {
-            configuration = self.tmp_ll.read().await?;
+            configuration = self.read_config().await?;
         }
 
         Ok(())
     }
 
-    async fn read_temp_raw(&mut self) -> Result<f32, Error<E>> {
+    /// Wait for the device to finish loading the EEPROM in the registers after power-up.
+    /// The `eeprom_busy` flag stays set for about 1.5ms after power-up and configuration written
+    /// during that window can be lost, so call this before configuring the device on fast booting MCUs.
+    /// Polls the [EEPROM] register to avoid clearing the `data_ready` flag of the configuration register.
+    /// Returns [Error::Timeout] if the device is still busy after 10ms
+    pub async fn wait_ready_after_power_up<D>(&mut self, delay: &mut D) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.wait_eeprom_timeout(delay, POWER_UP_TIMEOUT_US / 1000)
+            .await
+    }
+
+    /// Wait for the EEPROM to finish programming, polling every 100us with the delay.
+    /// A write typically completes in 7ms, see [EEPROM_WRITE_TIMEOUT_MS] for a safe bound.
+    /// Polls the [EEPROM] register to avoid clearing the `data_ready` flag of the configuration register.
+    /// Returns [Error::Timeout] if the device is still busy after `max_ms`, e.g. when failing its EEPROM program cycle.
+    /// The EEPROM is always read once, a `max_ms` of 0 only checks that the device is idle
+    pub async fn wait_eeprom_timeout<D>(
+        &mut self,
+        delay: &mut D,
+        max_ms: u32,
+    ) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        let polls = max_ms.saturating_mul(1000) / POLL_INTERVAL_US;
+        let mut waited = 0;
+        loop {
+            // Polled at least once, so an idle device passes even with a `max_ms` of 0
+            let eeprom: EEPROM = self.tmp_ll.read().await?;
+            if !eeprom.busy() {
+                return Ok(());
+            }
+            if waited >= polls {
+                return Err(Error::Timeout);
+            }
+            delay.delay_us(POLL_INTERVAL_US).await;
+            waited += 1;
+        }
+    }
+
+    /// Check if the first conversion completed since power-up or a reset.
+    /// Until then the temperature register holds the power-up value of -256°C,
+    /// so a read right after a reset returns this value instead of a real measurement.
+    /// Note that reading the temperature clears the data ready flag
+    pub async fn is_first_conversion_done(&mut self) -> Result<bool, Error<E>> {
+        let temp: Temperature = self.tmp_ll.read().await?;
+        Ok(u16::from(temp) != POWER_UP_TEMPERATURE)
+    }
+
+    /// Read the temperature, distinguishing a genuine -256°C from the power-up value of the register.
+    /// Returns [Error::NotYetConverted] if the temperature is the power-up value and the data ready flag is cleared,
+    /// which matters when approaching the bottom of the range.
+    /// Reads the configuration then the temperature, which clears the data ready and alert flags
+    pub async fn read_temp_converted(&mut self) -> Result<Celsius, Error<E>> {
+        let config = self.read_config().await?;
+        let counts = self.read_temp_raw_i16().await?;
+        if counts as u16 == POWER_UP_TEMPERATURE && !config.data_ready() {
+            return Err(Error::NotYetConverted);
+        }
+        Ok(self.celsius_from_counts(counts))
+    }
+
+    /// Wait for the first conversion after power-up or a [general call reset](Self::general_call_reset),
+    /// polling every 100us with the delay, so the first [read_temp](ContinuousHandler::read_temp) is real data.
+    /// Note that [reset](Self::reset) puts the device in shutdown, start a conversion before waiting.
+    /// Returns [Error::Timeout] if no conversion completed after 1s, the longest conversion with 64 averages
+    pub async fn wait_first_conversion<D>(&mut self, delay: &mut D) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        for _ in 0..FIRST_CONVERSION_TIMEOUT_US / POLL_INTERVAL_US {
+            if self.is_first_conversion_done().await? {
+                return Ok(());
+            }
+            delay.delay_us(POLL_INTERVAL_US).await;
+        }
+        Err(Error::Timeout)
+    }
+
+    /// Read the temperature register as two's complement counts of [CELCIUS_CONVERSION](crate::CELCIUS_CONVERSION) without any float conversion,
+    /// useful to log the raw values and convert them offline. The data ready flag is not checked
+    pub async fn read_temp_raw_i16(&mut self) -> Result<i16, Error<E>> {
         let temp: Temperature = self.tmp_ll.read().await?;
 
         // Convert to i16 for two complements
-        let val = (u16::from(temp) as i16) as f32 * CELCIUS_CONVERSION;
-        Ok(val)
-    }
-
-    async fn check_alert(&mut self) -> Result<Alert, Error<E>> {
-        let config: Configuration = self.tmp_ll.read().await?;
-        if config.high_alert() && config.low_alert() {
-            Ok(Alert::HighLow)
-        } else if config.high_alert() {
-            Ok(Alert::High)
-        } else if config.low_alert() {
-            Ok(Alert::Low)
+        let counts = u16::from(temp) as i16;
+        self.session.record(counts);
+        Ok(counts)
+    }
+
+    fn celsius_from_counts(&mut self, counts: i16) -> Celsius {
+        self.calibrated(counts_to_celsius(counts as u16))
+    }
+
+    /// Apply the calibration and call the observer with a temperature in celsius
+    fn calibrated(&mut self, mut val: f32) -> Celsius {
+        if let Some(calibration) = self.calibration.as_mut() {
+            val = calibration(val);
+        }
+        if let Some(observer) = self.observer.as_mut() {
+            observer(val);
+        }
+        Celsius(val)
+    }
+
+    async fn read_temp_raw(&mut self) -> Result<Celsius, Error<E>> {
+        let counts = self.read_temp_raw_i16().await?;
+        Ok(self.celsius_from_counts(counts))
+    }
+
+    /// Read a register, retrying up to `retries` times on bus errors and waiting the backoff between the attempts, if any
+    async fn read_raw_retry<D>(
+        &mut self,
+        addr: u8,
+        retries: u8,
+        mut backoff: Option<(&mut D, u32)>,
+    ) -> Result<u16, Error<E>>
+    where
+        D: DelayNs,
+    {
+        let mut attempt = 0;
+        loop {
+            match self.tmp_ll.read_raw(addr).await {
+                Err(ErrorLL::Bus(_)) if attempt < retries => {
+                    attempt += 1;
+                    if let Some((delay, backoff_us)) = &mut backoff {
+                        delay.delay_us(*backoff_us).await;
+                    }
+                }
+                res => return Ok(res?),
+            }
+        }
+    }
+
+    /// Read the alert flags in any mode, e.g. in thermal mode or after a [oneshot](Self::oneshot), without a continuous handler.
+    /// In alert mode the read clears the flags, in [thermal mode](TriggerMode::Thermal) the high alert flag stays set
+    /// until the temperature falls below the low limit.
+    /// Note that the data ready flag is also cleared by this read
+    pub async fn poll_alert(&mut self) -> Result<Alert, Error<E>> {
+        let config = self.read_config().await?;
+        Ok(Alert::from(config))
+    }
+
+    /// Read all the registers of [RegisterId::ALL] in a single bus transaction, see [Tmp117LL::snapshot].
+    /// Note that reading the temperature and configuration clears the data ready and alert flags
+    pub async fn snapshot(&mut self) -> Result<[u16; RegisterId::ALL.len()], Error<E>> {
+        Ok(self.tmp_ll.snapshot().await?)
+    }
+
+    /// Read every register of the device paired with its address, in address order, e.g. to attach a dump to a bug report.
+    /// Uses a single [snapshot](Self::snapshot) transaction.
+    /// Note that reading the temperature and configuration clears the data ready and alert flags
+    pub async fn dump_registers(&mut self) -> Result<[(u8, u16); RegisterId::ALL.len()], Error<E>> {
+        let values = self.snapshot().await?;
+        Ok(core::array::from_fn(|i| {
+            (RegisterId::ALL[i].address(), values[i])
+        }))
+    }
+
+    /// Read the raw value of any register by address, bypassing the typed registers.
+    /// Returns [Error::InvalidRegister] if the address is outside of the register map (0x00 to 0x0F)
+    pub async fn read_raw(&mut self, addr: u8) -> Result<u16, Error<E>> {
+        if addr > RegisterId::DeviceID.address() {
+            return Err(Error::InvalidRegister(addr));
+        }
+        Ok(self.tmp_ll.read_raw(addr).await?)
+    }
+
+    /// Write the raw value of any register by address, bypassing the typed registers.
+    /// Returns [Error::InvalidRegister] if the address is outside of the register map (0x00 to 0x0F).
+    ///
+    /// No validation is done on the value, reserved bits and settings are written as is.
+    /// Writing the configuration invalidates the [cached configuration](Self::cached_config)
+    pub async fn write_raw(&mut self, addr: u8, value: u16) -> Result<(), Error<E>> {
+        if addr > RegisterId::DeviceID.address() {
+            return Err(Error::InvalidRegister(addr));
+        }
+        self.tmp_ll.write_raw(addr, value).await?;
+        if addr == RegisterId::Configuration.address() {
+            self.config = None;
+        }
+        Ok(())
+    }
+
+    /// Check that the device is in a usable state, useful on startup or to recover after a brownout.
+    /// Reads the configuration, which clears the data ready and alert flags, and checks in order that:
+    /// - The soft reset bit is cleared, otherwise the device is in the middle of a reset: [Error::ResetInProgress]
+    /// - The conversion mode is not the reserved `0b10` pattern: [Error::InvalidMode]
+    /// - The EEPROM is not busy longer than the 10ms allowed for the power-up load,
+    ///   which would indicate a stuck EEPROM: [Error::EepromBusy]
+    pub async fn health_check<D>(&mut self, delay: &mut D) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        let raw = self
+            .tmp_ll
+            .read_raw(RegisterId::Configuration.address())
+            .await?;
+        // The mode bits are not meaningful during a reset, check the reset bit first
+        if raw & CONFIG_RESET_BIT != 0 {
+            return Err(Error::ResetInProgress);
+        }
+        decode_config::<E>(raw)?;
+
+        match self.wait_ready_after_power_up(delay).await {
+            Err(Error::Timeout) => Err(Error::EepromBusy),
+            res => res,
+        }
+    }
+
+    /// Acknowledge an interrupt of the ALERT pin and return its cause.
+    /// Reads the configuration once, which clears the alert flags, so the next alert can trigger the pin again.
+    /// Note that the data ready flag is also cleared by this read.
+    ///
+    /// This is meant to be called from the bottom half of the interrupt, doing an i2c transaction
+    /// directly in an interrupt handler is usually a bad idea: signal a task from the interrupt and call it from there
+    pub async fn handle_alert_interrupt(&mut self) -> Result<Alert, Error<E>> {
+        self.poll_alert().await
+    }
+
+    /// Set the high and low limits symmetrically around a center temperature in celsius,
+    /// e.g. `set_window(20.0, 3.0)` alerts if the temperature leaves 20 ± 3 °C.
+    /// The current low limit is read to write the limits in an order that never leaves the high limit below the low limit.
+    /// Returns [Error::InvalidLimits] if the half width is negative and [Error::OutOfRange] if a limit is outside of the registers range
+    pub async fn set_window(&mut self, center_c: f32, half_width_c: f32) -> Result<(), Error<E>> {
+        let high = center_c + half_width_c;
+        let low = center_c - half_width_c;
+        if low > high {
+            return Err(Error::InvalidLimits);
+        }
+        let (high, low) = (celsius_to_raw(high)?, celsius_to_raw(low)?);
+
+        // The device must never hold a high limit below the low limit, it could latch a spurious alert.
+        // Moving the window down past the current low limit needs the low limit written first
+        let current_low: LowLimit = self.tmp_ll.read().await?;
+        if (high as i16) < (u16::from(current_low) as i16) {
+            self.tmp_ll.write(LowLimit::from(low)).await?;
+            self.tmp_ll.write(HighLimit::from(high)).await?;
         } else {
-            Ok(Alert::None)
+            self.tmp_ll.write(HighLimit::from(high)).await?;
+            self.tmp_ll.write(LowLimit::from(low)).await?;
         }
+        Ok(())
+    }
+
+    /// Set the high limit in celsius, returns [Error::OutOfRange] if it is outside of the registers range
+    pub async fn set_high_limit(&mut self, high_c: f32) -> Result<(), Error<E>> {
+        self.tmp_ll
+            .write(HighLimit::from(celsius_to_raw(high_c)?))
+            .await?;
+        Ok(())
+    }
+
+    /// Set the low limit in celsius, returns [Error::OutOfRange] if it is outside of the registers range
+    pub async fn set_low_limit(&mut self, low_c: f32) -> Result<(), Error<E>> {
+        self.tmp_ll
+            .write(LowLimit::from(celsius_to_raw(low_c)?))
+            .await?;
+        Ok(())
+    }
+
+    /// Same as [set_high_limit](Self::set_high_limit) but [clamps](Celsius::clamp_to_range) the limit into the registers range
+    /// instead of returning an error
+    pub async fn set_high_limit_clamped(&mut self, high_c: f32) -> Result<(), Error<E>> {
+        let high = Celsius(high_c).clamp_to_range();
+        self.tmp_ll
+            .write(HighLimit::from(celsius_to_counts(high.0)))
+            .await?;
+        Ok(())
+    }
+
+    /// Same as [set_low_limit](Self::set_low_limit) but [clamps](Celsius::clamp_to_range) the limit into the registers range
+    /// instead of returning an error
+    pub async fn set_low_limit_clamped(&mut self, low_c: f32) -> Result<(), Error<E>> {
+        let low = Celsius(low_c).clamp_to_range();
+        self.tmp_ll
+            .write(LowLimit::from(celsius_to_counts(low.0)))
+            .await?;
+        Ok(())
+    }
+
+    /// Read the high limit in celsius
+    pub async fn get_high_limit(&mut self) -> Result<f32, Error<E>> {
+        let high: HighLimit = self.tmp_ll.read().await?;
+        Ok(counts_to_celsius(u16::from(high)))
+    }
+
+    /// Read the low limit in celsius
+    pub async fn get_low_limit(&mut self) -> Result<f32, Error<E>> {
+        let low: LowLimit = self.tmp_ll.read().await?;
+        Ok(counts_to_celsius(u16::from(low)))
+    }
+
+    /// Set the temperature offset in celsius, added to the measured temperature.
+    /// Returns [Error::OutOfRange] if it is outside of the registers range
+    pub async fn set_offset(&mut self, offset_c: f32) -> Result<(), Error<E>> {
+        self.tmp_ll
+            .write(TemperatureOffset::from(celsius_to_raw(offset_c)?))
+            .await?;
+        Ok(())
+    }
+
+    /// Read the temperature offset in celsius
+    pub async fn get_offset(&mut self) -> Result<f32, Error<E>> {
+        let offset: TemperatureOffset = self.tmp_ll.read().await?;
+        Ok(counts_to_celsius(u16::from(offset)))
+    }
+
+    /// Set the average, the other settings and the conversion mode are kept
+    pub async fn set_average(&mut self, average: Average) -> Result<(), Error<E>> {
+        self.edit_config(|r| r.set_average(average)).await
+    }
+
+    /// Set the conversion cycle, the other settings and the conversion mode are kept
+    pub async fn set_conversion(&mut self, conversion: Conversion) -> Result<(), Error<E>> {
+        self.edit_config(|r| r.set_conversion(conversion)).await
+    }
+
+    /// Read the average currently configured, e.g. loaded from the EEPROM after a reset.
+    /// Note that reading the configuration clears the data ready and alert flags
+    pub async fn get_average(&mut self) -> Result<Average, Error<E>> {
+        let config = self.read_config().await?;
+        Ok(config.average())
+    }
+
+    /// Read the conversion cycle currently configured, e.g. loaded from the EEPROM after a reset.
+    /// Note that reading the configuration clears the data ready and alert flags
+    pub async fn get_conversion(&mut self) -> Result<Conversion, Error<E>> {
+        let config = self.read_config().await?;
+        Ok(config.conversion())
+    }
+
+    /// Polarity of the alert pin as last written by the driver
+    fn active_polarity(&self) -> Polarity {
+        self.config.map_or(Polarity::ActiveLow, |c| c.polarity())
     }
 
     async fn set_alert(&mut self) -> Result<(), Error<E>> {
@@ -154,12 +853,12 @@ where
             if let AlertPin::Alert(_) = p {
             } else {
                 // If not, set it to alert
-                self.tmp_ll
-                    .edit(|r: &mut Configuration| {
-                        r.set_dr_alert(AlertPinSelect::Alert);
-                        r.set_polarity(Polarity::ActiveLow);
-                    })
-                    .await?;
+                let polarity = self.active_polarity();
+                self.edit_config(|r| {
+                    r.set_dr_alert(AlertPinSelect::Alert);
+                    r.set_polarity(polarity);
+                })
+                .await?;
             }
             self.alert = self.alert.take().map(|v| AlertPin::Alert(v.unwrap()));
         }
@@ -173,12 +872,12 @@ where
             if let AlertPin::DataReady(_) = p {
             } else {
                 // If not, set it to data ready
-                self.tmp_ll
-                    .edit(|r: &mut Configuration| {
-                        r.set_dr_alert(AlertPinSelect::DataReady);
-                        r.set_polarity(Polarity::ActiveLow);
-                    })
-                    .await?;
+                let polarity = self.active_polarity();
+                self.edit_config(|r| {
+                    r.set_dr_alert(AlertPinSelect::DataReady);
+                    r.set_polarity(polarity);
+                })
+                .await?;
             }
             self.alert = self.alert.take().map(|v| AlertPin::DataReady(v.unwrap()));
         }
@@ -186,14 +885,36 @@ where
     }
 
     async fn wait_for_data(&mut self) -> Result<(), Error<E>> {
+        self.wait_for_data_in(None).await
+    }
+
+    /// Wait for the data, returns [Error::WrongMode] if the device isn't in the expected mode
+    /// Only the configuration is read while waiting since reading the temperature would clear the data ready flag,
+    /// the temperature must be read once after the flag was seen set
+    async fn wait_for_data_in(&mut self, mode: Option<ConversionMode>) -> Result<(), Error<E>> {
+        let polarity = self.active_polarity();
+        let pin_wait = self.pin_wait;
         // If we have a pin
         if let Some(AlertPin::DataReady(p)) = &mut self.alert {
             loop {
-                // Wait for it to go low
-                p.wait_for_low().await.map_err(|_| Error::AlertPin)?;
+                // Wait for it to be active
+                wait_active(p, polarity, pin_wait).await?;
+
+                // The active pin is the data ready flag, skip the read of the configuration which would clear it.
+                // The temperature read following the wait clears it instead
+                let Some(mode) = mode else {
+                    break;
+                };
 
-                // Clear flag in register
-                let config: Configuration = self.tmp_ll.read().await?;
+                // The pin is borrowed, read through the low level driver
+                let raw = self
+                    .tmp_ll
+                    .read_raw(RegisterId::Configuration.address())
+                    .await?;
+                let config = decode_config(raw)?;
+                if mode != config.mode() {
+                    return Err(Error::WrongMode);
+                }
 
                 // Validate that the data is ready
                 if config.data_ready() {
@@ -203,7 +924,10 @@ where
         } else {
             // Loop while the alert is not ok
             loop {
-                let config: Configuration = self.tmp_ll.read().await?;
+                let config = self.read_config().await?;
+                if mode.is_some_and(|m| m != config.mode()) {
+                    return Err(Error::WrongMode);
+                }
                 if config.data_ready() {
                     break;
                 }
@@ -212,86 +936,277 @@ where
         Ok(())
     }
 
-    async fn wait_for_alert(&mut self) -> Result<Alert, Error<E>> {
-        if let Some(AlertPin::Alert(p)) = &mut self.alert {
-            p.wait_for_low().await.map_err(|_| Error::AlertPin)?;
-            self.check_alert().await
-        } else {
-            loop {
-                let alert = self.check_alert().await;
-                if let Ok(Alert::None) = alert {
-                    continue;
-                } else {
-                    return alert;
-                }
+    /// Wait for the data by polling the configuration every interval with the delay,
+    /// the callback is called with the elapsed milliseconds after every interval.
+    /// Returns [Error::Timeout] once `max_ms` elapsed, an interval of 0 is polled every millisecond
+    async fn wait_for_data_delay<D, F>(
+        &mut self,
+        delay: &mut D,
+        interval_ms: u32,
+        max_ms: u32,
+        mut on_interval: F,
+    ) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+        F: FnMut(u32),
+    {
+        // A 0 interval would poll without the elapsed time ever reaching the timeout
+        let interval_ms = interval_ms.max(1);
+        let mut elapsed: u32 = 0;
+        loop {
+            let config = self.read_config().await?;
+            if config.data_ready() {
+                return Ok(());
             }
+            if elapsed >= max_ms {
+                return Err(Error::Timeout);
+            }
+            delay.delay_ms(interval_ms).await;
+            elapsed = elapsed.saturating_add(interval_ms);
+            on_interval(elapsed);
         }
     }
 
-    async fn set_continuous(
+    /// Wait for the data by polling the configuration with the delay, returns [Error::Timeout] after `timeout_us`
+    async fn wait_for_data_timeout<D>(
         &mut self,
-        config: ContinuousConfig,
-    ) -> Result<ContinuousHandler<ADDR, T, E, P>, Error<E>> {
-        self.set_data_ready().await?;
-        if let Some(val) = config.high {
-            let high: HighLimit = ((val / CELCIUS_CONVERSION) as u16).into();
-            self.tmp_ll.write(high).await?;
-        }
-        if let Some(val) = config.low {
-            let low: LowLimit = ((val / CELCIUS_CONVERSION) as u16).into();
-            self.tmp_ll.write(low).await?;
-        }
-        if let Some(val) = config.offset {
-            let off: TemperatureOffset = ((val / CELCIUS_CONVERSION) as u16).into();
-            self.tmp_ll.write(off).await?;
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        let mut elapsed = 0;
+        loop {
+            let config = self.read_config().await?;
+            if config.data_ready() {
+                return Ok(());
+            }
+            if elapsed >= timeout_us {
+                return Err(Error::Timeout);
+            }
+            delay.delay_us(POLL_INTERVAL_US).await;
+            elapsed += POLL_INTERVAL_US;
         }
+    }
 
-        self.tmp_ll
-            .edit(|r: &mut Configuration| {
+    /// Wait for an alert by polling the configuration with the delay, returns [Error::Timeout] after `timeout_us`
+    async fn wait_for_alert_timeout<D>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<Alert, Error<E>>
+    where
+        D: DelayNs,
+    {
+        let mut elapsed = 0;
+        loop {
+            let alert = self.poll_alert().await?;
+            if !matches!(alert, Alert::None) {
+                return Ok(alert);
+            }
+            if elapsed >= timeout_us {
+                return Err(Error::Timeout);
+            }
+            delay.delay_us(POLL_INTERVAL_US).await;
+            elapsed += POLL_INTERVAL_US;
+        }
+    }
+
+    async fn wait_for_alert(&mut self) -> Result<Alert, Error<E>> {
+        let polarity = self.active_polarity();
+        let pin_wait = self.pin_wait;
+        if let Some(AlertPin::Alert(p)) = &mut self.alert {
+            wait_active(p, polarity, pin_wait).await?;
+            self.poll_alert().await
+        } else {
+            loop {
+                let alert = self.poll_alert().await;
+                if let Ok(Alert::None) = alert {
+                    continue;
+                } else {
+                    return alert;
+                }
+            }
+        }
+    }
+
+    async fn set_continuous(
+        &mut self,
+        config: ContinuousConfig,
+    ) -> Result<ContinuousHandler<'_, ADDR, T, E, P, O, K>, Error<E>> {
+        // Validate every value before writing any of them
+        let high = config.high.map(|v| celsius_to_raw(v.0)).transpose()?;
+        let low = config.low.map(|v| celsius_to_raw(v.0)).transpose()?;
+        let offset = config.offset.map(|v| celsius_to_raw(v.0)).transpose()?;
+
+        if let Some(val) = high {
+            self.tmp_ll.write(HighLimit::from(val)).await?;
+        }
+        if let Some(val) = low {
+            self.tmp_ll.write(LowLimit::from(val)).await?;
+        }
+        if let Some(val) = offset {
+            self.tmp_ll.write(TemperatureOffset::from(val)).await?;
+        }
+
+        // The alert pin is set to data ready in the same write as the mode, instead of its own read-modify-write
+        let has_pin = self.alert.is_some();
+        self.edit_config_complete(
+            |r| {
                 r.set_mode(ConversionMode::Continuous);
+                if has_pin {
+                    r.set_dr_alert(AlertPinSelect::DataReady);
+                }
+                r.set_polarity(config.polarity);
+                if let Some(trigger_mode) = config.trigger_mode {
+                    r.set_trigger_mode(trigger_mode);
+                }
                 r.set_average(config.average);
                 r.set_conversion(config.conversion);
-            })
-            .await?;
-        Ok(ContinuousHandler { tmp117: self })
+            },
+            // With a pin, the read clears a stale data ready flag which would keep the pin active
+            !has_pin && config.trigger_mode.is_some(),
+        )
+        .await?;
+        self.alert = self.alert.take().map(|v| AlertPin::DataReady(v.unwrap()));
+        self.session = SessionAccumulator::default();
+        Ok(ContinuousHandler {
+            tmp117: self,
+            cycle_ms: config.conversion.cycle_time_us(config.average) / 1000,
+            last_sample_ms: None,
+        })
     }
 
-    async fn set_oneshot(&mut self, average: Average) -> Result<(), Error<E>> {
+    async fn set_oneshot(
+        &mut self,
+        average: Average,
+        conversion: Option<Conversion>,
+    ) -> Result<(), Error<E>> {
         self.set_data_ready().await?;
-        self.tmp_ll
-            .edit(|r: &mut Configuration| {
-                r.set_mode(ConversionMode::OneShot);
-                r.set_average(average);
-            })
-            .await?;
+        let polarity = self.oneshot_polarity;
+        self.edit_config(|r| {
+            r.set_mode(ConversionMode::OneShot);
+            r.set_polarity(polarity);
+            r.set_average(average);
+            if let Some(conversion) = conversion {
+                r.set_conversion(conversion);
+            }
+        })
+        .await?;
         Ok(())
     }
 
-    async fn set_shutdown(&mut self) -> Result<(), Error<E>> {
-        self.tmp_ll
-            .edit(|r: &mut Configuration| {
-                r.set_mode(ConversionMode::Shutdown);
-            })
-            .await?;
+    /// Put the device in shutdown, stopping any conversion until the next mode change
+    pub async fn set_shutdown(&mut self) -> Result<(), Error<E>> {
+        self.edit_config_skip(|r| {
+            r.set_mode(ConversionMode::Shutdown);
+        })
+        .await?;
         Ok(())
     }
 
-    /// Resets the device and put it in shutdown
+    /// Resets the device and put it in shutdown, waiting the typical 2ms of the reset
     pub async fn reset<D>(&mut self, delay: &mut D) -> Result<(), Error<E>>
     where
         D: DelayNs,
     {
-        self.tmp_ll
-            .edit(|r: &mut Configuration| {
-                r.set_reset(true);
-            })
-            .await?;
-        delay.delay_ms(2).await;
+        self.reset_with_delay(delay, RESET_DELAY_MS).await
+    }
+
+    /// Same as [reset](Self::reset) but waits `ms` for the reset to complete,
+    /// for marginal parts that are not quite ready after the typical 2ms
+    pub async fn reset_with_delay<D>(&mut self, delay: &mut D, ms: u32) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.edit_config(|r| {
+            r.set_reset(true);
+        })
+        .await?;
+        delay.delay_ms(ms).await;
+        self.forget_device_state();
         self.set_shutdown().await
     }
 
-    /// Write data to user eeprom. Note that this is blocking because we wait for write on the eeprom to complete
+    /// The reset reloads the EEPROM, which may select another function for the alert pin
+    fn forget_device_state(&mut self) {
+        self.config = None;
+        self.alert = self.alert.take().map(|v| AlertPin::Unkown(v.unwrap()));
+    }
+
+    /// Reload the configuration, limits and offset stored in the EEPROM, leaving the device in the stored mode
+    /// unlike [reset](Self::reset) which forces the shutdown. Only this device is affected, unlike the
+    /// [general call reset](Self::general_call_reset). The sequence is:
+    /// 1. Set the soft reset bit of the configuration, the device reloads the EEPROM like on power-up
+    /// 2. Wait the 2ms of the reset
+    /// 3. Poll the [EEPROM] register until the busy flag clears, see [wait_ready_after_power_up](Self::wait_ready_after_power_up)
+    ///
+    /// Returns [Error::Timeout] if the EEPROM is still busy 10ms after the reset
+    pub async fn reload_eeprom<D>(&mut self, delay: &mut D) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.edit_config(|r| {
+            r.set_reset(true);
+        })
+        .await?;
+        self.forget_device_state();
+        delay.delay_ms(RESET_DELAY_MS).await;
+        self.wait_ready_after_power_up(delay).await
+    }
+
+    /// Resets every device on the bus with the I2C general call and waits the 2ms of the reset.
+    /// This affects all the devices supporting the general call on the bus, not only the TMP117s, which is handy at boot
+    /// to put every sensor in a known state at once. The devices reload their configuration from the EEPROM, see [reset](Self::reset)
+    /// to reset only this device
+    pub async fn general_call_reset<D>(&mut self, delay: &mut D) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.tmp_ll.general_call_reset().await?;
+        self.forget_device_state();
+        delay.delay_ms(RESET_DELAY_MS).await;
+        Ok(())
+    }
+
+    /// Write data to user eeprom. Note that this is blocking because we wait for write on the eeprom to complete.
+    /// The first value is ignored since [UEEPROM1] holds the [NIST traceability ID](Self::read_nist_id),
+    /// use [write_eeprom_including_nist](Self::write_eeprom_including_nist) to overwrite it
     pub async fn write_eeprom(&mut self, values: [u16; 3]) -> Result<(), Error<E>> {
+        self.wait_eeprom().await?;
+        self.tmp_ll.write(UEEPROM2::from(values[1])).await?;
+
+        self.wait_eeprom().await?;
+        self.tmp_ll.write(UEEPROM3::from(values[2])).await?;
+
+        Ok(())
+    }
+
+    /// Same as [write_eeprom](Self::write_eeprom) but waits at most [EEPROM_WRITE_TIMEOUT_MS] for each write to complete
+    /// instead of spinning on the busy flag, returns [Error::Timeout] if the EEPROM stays busy
+    pub async fn write_eeprom_timeout<D>(
+        &mut self,
+        values: [u16; 3],
+        delay: &mut D,
+    ) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.wait_eeprom_timeout(delay, EEPROM_WRITE_TIMEOUT_MS)
+            .await?;
+        self.tmp_ll.write(UEEPROM2::from(values[1])).await?;
+
+        self.wait_eeprom_timeout(delay, EEPROM_WRITE_TIMEOUT_MS)
+            .await?;
+        self.tmp_ll.write(UEEPROM3::from(values[2])).await?;
+
+        Ok(())
+    }
+
+    /// Same as [write_eeprom](Self::write_eeprom) but also writes the first value to [UEEPROM1],
+    /// destroying the NIST traceability ID
+    pub async fn write_eeprom_including_nist(&mut self, values: [u16; 3]) -> Result<(), Error<E>> {
         self.wait_eeprom().await?;
         self.tmp_ll.write(UEEPROM1::from(values[0])).await?;
 
@@ -304,6 +1219,116 @@ where
         Ok(())
     }
 
+    async fn set_eeprom_unlock(&mut self, unlock: bool) -> Result<(), Error<E>> {
+        self.tmp_ll
+            .edit(|r: &mut EEPROM| {
+                r.set_unlock(unlock);
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Unlock the EEPROM, waiting for any pending programming to complete first.
+    /// While unlocked, every write to the configuration, limits, offset or user EEPROM registers also programs
+    /// the EEPROM so the value survives a power cycle. Each programming takes about 7ms during which the EEPROM is busy,
+    /// so the writes are slow: [lock](Self::lock_eeprom) it as soon as the values are written
+    pub async fn unlock_eeprom(&mut self) -> Result<(), Error<E>> {
+        self.wait_eeprom().await?;
+        self.set_eeprom_unlock(true).await
+    }
+
+    /// Lock the EEPROM once the pending programming completes, the writes to the registers are volatile again
+    pub async fn lock_eeprom(&mut self) -> Result<(), Error<E>> {
+        self.wait_eeprom().await?;
+        self.set_eeprom_unlock(false).await
+    }
+
+    /// Program the EEPROM so the device boots with the given settings.
+    /// Unlocks the EEPROM, writes the configuration which programs the EEPROM backed bits,
+    /// waits for the programming to complete and locks the EEPROM back.
+    ///
+    /// The mode, conversion cycle, average, trigger mode, polarity and alert pin select bits are EEPROM backed,
+    /// the current mode is also stored so the device boots in it.
+    /// The soft reset bit and the status flags (alerts, data ready and EEPROM busy) are volatile and never persisted
+    pub async fn persist_config(
+        &mut self,
+        average: Average,
+        conversion: Conversion,
+        polarity: Polarity,
+        trigger_mode: TriggerMode,
+    ) -> Result<(), Error<E>> {
+        self.unlock_eeprom().await?;
+        self.edit_config(|r| {
+            r.set_average(average);
+            r.set_conversion(conversion);
+            r.set_polarity(polarity);
+            r.set_trigger_mode(trigger_mode);
+        })
+        .await?;
+        self.lock_eeprom().await
+    }
+
+    /// Read the NIST traceability ID stored in [UEEPROM1]
+    pub async fn read_nist_id(&mut self) -> Result<u16, Error<E>> {
+        let u1: UEEPROM1 = self.tmp_ll.read().await?;
+        Ok(u1.into())
+    }
+
+    /// Read a single user EEPROM register, index 0 to 2 maps to [UEEPROM1] to [UEEPROM3].
+    /// Returns [Error::InvalidIndex] for any other index
+    pub async fn read_user_eeprom(&mut self, index: u8) -> Result<u16, Error<E>> {
+        let addr = user_eeprom_address(index)?;
+        Ok(self.tmp_ll.read_raw(addr).await?)
+    }
+
+    /// Write a single user EEPROM register without touching the others, index 1 and 2 maps to [UEEPROM2] and [UEEPROM3].
+    /// Returns [Error::InvalidIndex] for any other index, including 0 since [UEEPROM1] holds the [NIST traceability ID](Self::read_nist_id),
+    /// use [write_user_eeprom_including_nist](Self::write_user_eeprom_including_nist) to overwrite it.
+    /// Note that this is blocking because we wait for the write on the eeprom to complete
+    pub async fn write_user_eeprom(&mut self, index: u8, value: u16) -> Result<(), Error<E>> {
+        if index == 0 {
+            return Err(Error::InvalidIndex(index));
+        }
+        self.write_user_eeprom_including_nist(index, value).await
+    }
+
+    /// Same as [write_user_eeprom](Self::write_user_eeprom) but also accepts the index 0, destroying the NIST traceability ID
+    pub async fn write_user_eeprom_including_nist(
+        &mut self,
+        index: u8,
+        value: u16,
+    ) -> Result<(), Error<E>> {
+        let addr = user_eeprom_address(index)?;
+        self.wait_eeprom().await?;
+        self.tmp_ll.write_raw(addr, value).await?;
+        self.wait_eeprom().await
+    }
+
+    /// Program the current configuration, limits and offset in the EEPROM so the device boots with them.
+    /// Unlocks the EEPROM, writes back every register as read from the device waiting for the programming
+    /// to complete after each write, and locks the EEPROM back. Takes about 30ms.
+    /// Note that reading the configuration clears the data ready and alert flags
+    pub async fn save_config_to_eeprom(&mut self) -> Result<(), Error<E>> {
+        self.unlock_eeprom().await?;
+
+        // Writing the configuration back as read programs it
+        self.edit_config(|_| {}).await?;
+        self.wait_eeprom().await?;
+
+        let high: HighLimit = self.tmp_ll.read().await?;
+        self.tmp_ll.write(high).await?;
+        self.wait_eeprom().await?;
+
+        let low: LowLimit = self.tmp_ll.read().await?;
+        self.tmp_ll.write(low).await?;
+        self.wait_eeprom().await?;
+
+        let offset: TemperatureOffset = self.tmp_ll.read().await?;
+        self.tmp_ll.write(offset).await?;
+
+        self.lock_eeprom().await
+    }
+
     /// Read the data from the eeprom
     pub async fn read_eeprom(&mut self) -> Result<[u16; 3], Error<E>> {
         let u1: UEEPROM1 = self.tmp_ll.read().await?;
@@ -314,8 +1339,24 @@ where
     }
 
     /// Wait for data and read the temperature in celsius and goes to shutdown since it's a oneshot
-    pub async fn oneshot(&mut self, average: Average) -> Result<f32, Error<E>> {
-        self.set_oneshot(average).await?;
+    pub async fn oneshot(&mut self, average: Average) -> Result<Celsius, Error<E>> {
+        self.set_oneshot(average, None).await?;
+        self.wait_for_data().await?;
+
+        let res = self.read_temp_raw().await?;
+        self.set_shutdown().await?;
+        Ok(res)
+    }
+
+    /// Same as [oneshot](Self::oneshot) but also sets the conversion cycle.
+    /// The duration of a oneshot only depends on the average, the conversion cycle is the one kept
+    /// for a following [continuous](Self::continuous) mode, so it isn't inherited from a previous session
+    pub async fn oneshot_with(
+        &mut self,
+        average: Average,
+        conversion: Conversion,
+    ) -> Result<Celsius, Error<E>> {
+        self.set_oneshot(average, Some(conversion)).await?;
         self.wait_for_data().await?;
 
         let res = self.read_temp_raw().await?;
@@ -323,69 +1364,611 @@ where
         Ok(res)
     }
 
+    /// Same as [oneshot](Self::oneshot) but returns the temperature as any [FromRawTemperature] type.
+    /// The calibration and read observer are not applied since they work on celsius floats
+    pub async fn oneshot_as<U: FromRawTemperature>(
+        &mut self,
+        average: Average,
+    ) -> Result<U, Error<E>> {
+        self.set_oneshot(average, None).await?;
+        self.wait_for_data().await?;
+
+        let counts = self.read_temp_raw_i16().await?;
+        self.set_shutdown().await?;
+        Ok(U::from_raw(counts))
+    }
+
+    /// Same as [oneshot](Self::oneshot) but returns the temperature in fahrenheit
+    pub async fn oneshot_fahrenheit(&mut self, average: Average) -> Result<f32, Error<E>> {
+        Ok(celsius_to_fahrenheit(self.oneshot(average).await?.0))
+    }
+
+    /// Same as [oneshot](Self::oneshot) but returns the temperature in kelvin
+    pub async fn oneshot_kelvin(&mut self, average: Average) -> Result<f32, Error<E>> {
+        Ok(celsius_to_kelvin(self.oneshot(average).await?.0))
+    }
+
+    /// Do a [oneshot](Self::oneshot) without busy polling the data ready flag, which can be cleared by a read of the
+    /// configuration happening while the conversion completes, see the [TI forum](https://e2e.ti.com/support/sensors-group/sensors/f/sensors-forum/1019457/tmp117-data_ready-flag-cleared-incorrectly-if-data-becomes-ready-during-read-of-configuration-register).
+    /// Sleeps for the expected conversion time of the average before reading the configuration, then polls it with the delay
+    /// and returns [Error::Timeout] if the data is still not ready `timeout_ms` after the start of the conversion.
+    /// Like [oneshot](Self::oneshot) the device is put in shutdown afterwards, also when the conversion timed out
+    pub async fn oneshot_with_timeout<D>(
+        &mut self,
+        average: Average,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<Celsius, Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.set_oneshot(average, None).await?;
+        let conversion_us = average.conversion_time_us();
+        delay.delay_us(conversion_us).await;
+        let res = self
+            .wait_for_data_timeout(
+                delay,
+                timeout_ms
+                    .saturating_mul(1000)
+                    .saturating_sub(conversion_us),
+            )
+            .await;
+        if let Err(e) = res {
+            // Don't leave a conversion that didn't complete running, the timeout is the error reported
+            let _ = self.set_shutdown().await;
+            return Err(e);
+        }
+
+        let res = self.read_temp_raw().await?;
+        self.set_shutdown().await?;
+        Ok(res)
+    }
+
+    /// Do a [oneshot](Self::oneshot) sleeping once for the [conversion time](Average::conversion_time_us) of the average
+    /// instead of polling the data ready flag, then read the temperature without reading the configuration.
+    /// The duration of a oneshot only depends on the average, the conversion cycle doesn't apply
+    pub async fn oneshot_with_delay<D>(
+        &mut self,
+        average: Average,
+        delay: &mut D,
+    ) -> Result<Celsius, Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.set_oneshot(average, None).await?;
+        delay.delay_us(average.conversion_time_us()).await;
+        let res = self.read_temp_raw().await?;
+        self.set_shutdown().await?;
+        Ok(res)
+    }
+
+    /// Do a [oneshot](Self::oneshot) and feed the temperature to the filter, returns the filtered temperature in celsius
+    pub async fn read_filtered(
+        &mut self,
+        average: Average,
+        filter: &mut OneShotFilter,
+    ) -> Result<f32, Error<E>> {
+        let val = self.oneshot(average).await?;
+        Ok(filter.update(val.0))
+    }
+
+    /// Set the device to continuous and return the handler, without a closure.
+    /// The handler borrows the driver until it is [stopped](ContinuousHandler::stop), which puts the device back in shutdown.
+    /// Useful to keep the handler across the iterations of a state machine.
+    /// Like [continuous](Self::continuous), nothing shuts the device down if the handler is dropped without being stopped
+    pub async fn start_continuous(
+        &mut self,
+        config: ContinuousConfig,
+    ) -> Result<ContinuousHandler<'_, ADDR, T, E, P, O, K>, Error<E>> {
+        self.set_continuous(config).await
+    }
+
     /// Pass a config and closure for the continuous mode.
     /// The device gets set to continuous, then the function is called with the handler
-    /// and finally the device is shutdown
-    /// A pointer is passed since lifetime cannot be described for async closure in this situation
-    pub async fn continuous<F, Fut>(
-        &mut self,
+    /// and finally the device is shutdown with the handler returned by the closure.
+    /// The handler borrows the driver and is given back by the closure since the lifetime of an argument
+    /// can't be tied to the future returned by a closure, e.g. `|mut t| async move { t.wait_temp().await?; Ok(t) }`
+    ///
+    /// Unlike the blocking driver, the device can't be shutdown if the closure panics, returns an error or if the future is dropped
+    /// since a drop guard cannot await the bus. Call [reset](Tmp117::reset) or run a [oneshot](Tmp117::oneshot)
+    /// to put the device back in shutdown if the future may be cancelled.
+    ///
+    /// The driver is mutably borrowed for the whole call, so no other mode operation such as a
+    /// [oneshot](Self::oneshot) can be interleaved with the continuous reads
+    ///
+    /// Entering the continuous mode takes one write per limit and offset set in the config, then a read and a write
+    /// of the configuration. The read is skipped when the [cached configuration is used](Self::set_use_cached_config),
+    /// or without an alert pin when the [trigger mode](ContinuousConfig::trigger_mode) is set and the driver already
+    /// wrote the configuration, since every field is then known: the configuration takes a single write instead of 2 transactions.
+    /// With an alert pin, the data ready function is selected in that same write, where it previously took
+    /// its own read and write: the configuration went from 4 to 2 transactions.
+    /// With an alert pin the read is always done, it clears a data ready flag left set by a previous conversion
+    /// which would keep the pin active and make the first wait return the previous temperature
+    pub async fn continuous<'a, F, Fut>(
+        &'a mut self,
         config: ContinuousConfig,
         f: F,
     ) -> Result<(), Error<E>>
     where
-        F: FnOnce(ContinuousHandler<ADDR, T, E, P>) -> Fut,
-        Fut: Future<Output = Result<(), Error<E>>>,
+        F: FnOnce(ContinuousHandler<'a, ADDR, T, E, P, O, K>) -> Fut,
+        Fut: Future<Output = Result<ContinuousHandler<'a, ADDR, T, E, P, O, K>, Error<E>>>,
     {
         let continuous = self.set_continuous(config).await?;
-        f(continuous).await?;
-        self.set_shutdown().await
+        let continuous = f(continuous).await?;
+        continuous.stop().await
+    }
+
+    /// Pass a config and closure for the thermal mode, the device converts continuously like in [continuous](Self::continuous)
+    /// with the [trigger mode](ContinuousConfig::trigger_mode) forced to thermal so the alert acts as a thermostat:
+    /// the high limit is the trip point and the low limit the hysteresis release point.
+    /// The high alert is set when the temperature goes above the high limit and stays set until it drops below the low limit,
+    /// reading the configuration doesn't clear it. The low alert is always 0 in this mode.
+    /// Returns [Error::InvalidLimits] if both limits are given and the low limit is above the high limit
+    pub async fn thermal<'a, F, Fut>(
+        &'a mut self,
+        mut config: ContinuousConfig,
+        f: F,
+    ) -> Result<(), Error<E>>
+    where
+        F: FnOnce(ContinuousHandler<'a, ADDR, T, E, P, O, K>) -> Fut,
+        Fut: Future<Output = Result<ContinuousHandler<'a, ADDR, T, E, P, O, K>, Error<E>>>,
+    {
+        if let (Some(high), Some(low)) = (config.high, config.low) {
+            if low > high {
+                return Err(Error::InvalidLimits);
+            }
+        }
+        config.trigger_mode = Some(TriggerMode::Thermal);
+        let continuous = self.set_continuous(config).await?;
+        let continuous = f(continuous).await?;
+        continuous.stop().await
+    }
+
+    /// Same as [continuous](Self::continuous) but returns the [stats](SessionStats) of the temperatures read during the session.
+    /// The stats are computed from the register values, without the calibration
+    pub async fn continuous_with_stats<'a, F, Fut>(
+        &'a mut self,
+        config: ContinuousConfig,
+        f: F,
+    ) -> Result<SessionStats, Error<E>>
+    where
+        F: FnOnce(ContinuousHandler<'a, ADDR, T, E, P, O, K>) -> Fut,
+        Fut: Future<Output = Result<ContinuousHandler<'a, ADDR, T, E, P, O, K>, Error<E>>>,
+    {
+        let continuous = self.set_continuous(config).await?;
+        let continuous = f(continuous).await?;
+        let stats = continuous.stats();
+        continuous.stop().await?;
+        Ok(stats)
     }
 }
 
-/// Handler for the continuous mode
-///
-/// # Safety
-/// Note that it is only safe to use in the [Tmp117::continuous] closure since
-/// it uses a pointer to the tmp117 to circuvent issues with async closure lifetime
-pub struct ContinuousHandler<const ADDR: u8, T, E, P> {
-    tmp117: *mut Tmp117<ADDR, T, E, P>,
+/// Wait for the pin to be at its active level or to transition to it
+async fn wait_active<P: Wait, E>(
+    pin: &mut P,
+    polarity: Polarity,
+    pin_wait: PinWait,
+) -> Result<(), Error<E>> {
+    match (polarity, pin_wait) {
+        (Polarity::ActiveLow, PinWait::Level) => pin.wait_for_low().await,
+        (Polarity::ActiveHigh, PinWait::Level) => pin.wait_for_high().await,
+        (Polarity::ActiveLow, PinWait::Edge) => pin.wait_for_falling_edge().await,
+        (Polarity::ActiveHigh, PinWait::Edge) => pin.wait_for_rising_edge().await,
+    }
+    .map_err(|_| Error::AlertPin)
+}
+
+/// Handler for the continuous mode, borrows the driver for the whole session.
+/// Unlike the blocking handler, dropping it doesn't shutdown the device since a drop can't await the bus:
+/// call [stop](Self::stop), which returns the error of the shutdown, before letting it go
+#[must_use = "the device stays in continuous mode until the handler is stopped"]
+pub struct ContinuousHandler<'a, const ADDR: u8, T, E, P, O = fn(f32), K = fn(f32) -> f32> {
+    tmp117: &'a mut Tmp117<ADDR, T, E, P, O, K>,
+    cycle_ms: u32,
+    last_sample_ms: Option<u32>,
 }
 
-impl<'a, const ADDR: u8, T, E, P> ContinuousHandler<ADDR, T, E, P>
+impl<'a, const ADDR: u8, T, E, P, O, K> ContinuousHandler<'a, ADDR, T, E, P, O, K>
 where
     T: I2c<SevenBitAddress, Error = E>,
     E: embedded_hal::i2c::Error + Copy,
     P: Wait,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
 {
-    /// Read the temperature in celsius, return an error if the value of the temperature is not valid
-    pub async fn read_temp(&mut self) -> Result<f32, Error<E>> {
-        let tmp117 = unsafe { &mut *self.tmp117 };
-        let config: Configuration = tmp117.tmp_ll.read().await?;
+    /// Put the device back in shutdown and end the session, the only way to shutdown the device from the handler
+    pub async fn stop(self) -> Result<(), Error<E>> {
+        self.tmp117.set_shutdown().await
+    }
+
+    /// Read the temperature in celsius, return an error if the value of the temperature is not ready.
+    ///
+    /// The configuration is read before the temperature: reading either register clears the data ready flag,
+    /// so reading the temperature first would always report the data as not ready. Reading the temperature right
+    /// after the flag was seen set returns the conversion that set it, a conversion completing in between only
+    /// updates the temperature to a newer value
+    pub async fn read_temp(&mut self) -> Result<Celsius, Error<E>> {
+        let config = self.tmp117.read_config().await?;
+        if !config.data_ready() {
+            return Err(Error::DataNotReady);
+        }
+
+        self.tmp117.read_temp_raw().await
+    }
+
+    /// Same as [read_temp](Self::read_temp) but retries every register read up to `retries` times on [bus errors](Error::Bus),
+    /// e.g. a NACK on a noisy bus. The other errors, such as [Error::DataNotReady], are returned right away.
+    /// Each register is retried on its own so a failed temperature read doesn't read the configuration again, which would clear the data ready flag
+    pub async fn read_temp_retry(&mut self, retries: u8) -> Result<Celsius, Error<E>> {
+        self.read_temp_retry_in(retries, None::<(&mut NoDelay, u32)>)
+            .await
+    }
+
+    /// Same as [read_temp_retry](Self::read_temp_retry) but waits `backoff_us` with the delay before every retry
+    pub async fn read_temp_retry_with_backoff<D>(
+        &mut self,
+        retries: u8,
+        delay: &mut D,
+        backoff_us: u32,
+    ) -> Result<Celsius, Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.read_temp_retry_in(retries, Some((delay, backoff_us)))
+            .await
+    }
+
+    async fn read_temp_retry_in<D>(
+        &mut self,
+        retries: u8,
+        mut backoff: Option<(&mut D, u32)>,
+    ) -> Result<Celsius, Error<E>>
+    where
+        D: DelayNs,
+    {
+        let backoff_ref = backoff.as_mut().map(|(d, us)| (&mut **d, *us));
+        let config = self
+            .tmp117
+            .read_raw_retry(RegisterId::Configuration.address(), retries, backoff_ref)
+            .await?;
+        let config = decode_config(config)?;
+        if !config.data_ready() {
+            return Err(Error::DataNotReady);
+        }
+
+        let counts = self
+            .tmp117
+            .read_raw_retry(RegisterId::Temperature.address(), retries, backoff)
+            .await? as i16;
+        self.tmp117.session.record(counts);
+        Ok(self.tmp117.celsius_from_counts(counts))
+    }
+
+    /// Read the temperature in hundredths of a degree fahrenheit, return an error if the value of the temperature is not ready.
+    /// Uses [fahrenheit_centi_from_counts] so no float is involved, the calibration and read observer are not applied
+    pub async fn read_temp_fahrenheit_centi(&mut self) -> Result<i32, Error<E>> {
+        let config = self.tmp117.read_config().await?;
+        if !config.data_ready() {
+            return Err(Error::DataNotReady);
+        }
+
+        let counts = self.tmp117.read_temp_raw_i16().await?;
+        Ok(fahrenheit_centi_from_counts(counts))
+    }
+
+    /// Read the configuration once then the temperature, returning both with the alert and data ready flags.
+    /// Reading the configuration clears the flags, so this avoids losing them between a [read_temp](Self::read_temp)
+    /// and a [get_alert](Self::get_alert). The temperature is returned even if the data is not ready
+    pub async fn read_measurement(&mut self) -> Result<Measurement, Error<E>> {
+        let config = self.tmp117.read_config().await?;
+        let celsius = self.tmp117.read_temp_raw().await?;
+        Ok(Measurement {
+            celsius,
+            alert: Alert::from(config),
+            data_ready: config.data_ready(),
+        })
+    }
+
+    /// Same as [read_measurement](Self::read_measurement) but returns a compact [MeasurementFrame] with the raw temperature,
+    /// the calibration and read observer are not applied
+    pub async fn read_frame(&mut self) -> Result<MeasurementFrame, Error<E>> {
+        let config = self.tmp117.read_config().await?;
+        let counts = self.tmp117.read_temp_raw_i16().await?;
+        Ok(MeasurementFrame::new(counts, config))
+    }
+
+    /// Read the temperature as any [FromRawTemperature] type, return an error if the value of the temperature is not ready.
+    /// The calibration and read observer are not applied since they work on celsius floats
+    pub async fn read_temp_as<U: FromRawTemperature>(&mut self) -> Result<U, Error<E>> {
+        let config = self.tmp117.read_config().await?;
+        if !config.data_ready() {
+            return Err(Error::DataNotReady);
+        }
+
+        let counts = self.tmp117.read_temp_raw_i16().await?;
+        Ok(U::from_raw(counts))
+    }
+
+    /// Read the temperature in celsius with its saturation status, return an error if the value of the temperature is not ready.
+    /// A sensor pinned at the limits of the register usually indicates a wiring fault or an extreme out of range condition.
+    /// Since the data ready flag must be set, the –256 °C reset value is never reported as a saturation.
+    ///
+    /// Also verifies that the device is still in continuous mode and returns [Error::WrongMode] otherwise,
+    /// catching another task or a glitch having changed the mode. The mode is decoded from the configuration
+    /// read done to check the data ready flag, so it costs the same two transactions as [read_temp](Self::read_temp)
+    pub async fn read_temp_checked(&mut self) -> Result<(Celsius, TempStatus), Error<E>> {
+        let config = self.tmp117.read_config().await?;
+        if config.mode() != ConversionMode::Continuous {
+            return Err(Error::WrongMode);
+        }
         if !config.data_ready() {
             return Err(Error::DataNotReady);
         }
 
-        tmp117.read_temp_raw().await
+        let counts = self.tmp117.read_temp_raw_i16().await?;
+        Ok((
+            self.tmp117.celsius_from_counts(counts),
+            TempStatus::from(counts),
+        ))
+    }
+
+    /// Wait for the data to be ready and read the temperature in celsius.
+    /// With an alert pin, the temperature is read once the pin is active without reading the configuration,
+    /// saving a transaction per sample and avoiding the data ready flag being cleared by the poll of the configuration
+    pub async fn wait_temp(&mut self) -> Result<Celsius, Error<E>> {
+        self.tmp117.set_data_ready().await?;
+        self.tmp117.wait_for_data().await?;
+        self.tmp117.read_temp_raw().await
+    }
+
+    /// Turn the handler into a never ending stream of [Celsius] temperatures, each item [waits](Self::wait_temp)
+    /// for the alert pin in data ready mode, or polls the configuration without one, and reads the temperature.
+    /// Errors are yielded without ending the stream. Plugs the sensor into the `select!` and stream combinators.
+    ///
+    /// The handler is moved in the future of each item rather than borrowed by the stream, so no allocation is needed.
+    /// The device is left in continuous mode when the stream is dropped, like a handler dropped without being stopped
+    #[cfg(feature = "futures")]
+    pub fn into_stream(self) -> impl futures::Stream<Item = Result<Celsius, Error<E>>> + 'a {
+        futures::stream::unfold(self, |mut handler| async move {
+            let temp = handler.wait_temp().await;
+            Some((temp, handler))
+        })
+    }
+
+    /// Wait for `n` successive conversions and return their average in celsius, a software moving window complementing
+    /// the hardware [Average] without its long cycle times. The counts are summed and scaled once, the calibration and
+    /// read observer are applied to the average. At least one conversion is read
+    pub async fn read_averaged(&mut self, n: usize) -> Result<Celsius, Error<E>> {
+        self.tmp117.set_data_ready().await?;
+        let n = n.max(1);
+        let mut sum = 0i64;
+        for _ in 0..n {
+            self.tmp117.wait_for_data().await?;
+            sum += self.tmp117.read_temp_raw_i16().await? as i64;
+        }
+        let mean = scale_counts(sum as f32 / n as f32);
+        Ok(self.tmp117.calibrated(mean))
+    }
+
+    /// Wait for the data to be ready and read the temperature in celsius, calling `on_progress` with the elapsed
+    /// milliseconds every interval while waiting. Useful to give feedback with long cycles, up to 16s with [Conversion::Ms16000].
+    /// The configuration is polled every interval with the delay instead of continuously, an interval of 0 polls every millisecond.
+    /// Returns [Error::Timeout] if the data is still not ready after two conversion cycles
+    pub async fn wait_temp_with_progress<D, F>(
+        &mut self,
+        delay: &mut D,
+        interval_ms: u32,
+        on_progress: F,
+    ) -> Result<Celsius, Error<E>>
+    where
+        D: DelayNs,
+        F: FnMut(u32),
+    {
+        self.tmp117
+            .wait_for_data_delay(
+                delay,
+                interval_ms,
+                self.cycle_ms.saturating_mul(DATA_TIMEOUT_CYCLES),
+                on_progress,
+            )
+            .await?;
+        self.tmp117.read_temp_raw().await
+    }
+
+    /// Wait for the data to be ready and read the temperature in celsius.
+    /// Returns [Error::WrongMode] if the device is not in continuous mode anymore.
+    /// The mode is checked on every read of the configuration, without additional transactions.
+    /// Note that with an alert pin the device won't toggle it if it was shutdown, so the wait can still block
+    pub async fn wait_temp_checked(&mut self) -> Result<Celsius, Error<E>> {
+        self.tmp117.set_data_ready().await?;
+        self.tmp117
+            .wait_for_data_in(Some(ConversionMode::Continuous))
+            .await?;
+        self.tmp117.read_temp_raw().await
+    }
+
+    /// Collect successive readings in the buffer and compute their noise statistics in raw counts.
+    /// Useful to pick an averaging empirically, a higher [Average] should reduce the variance.
+    /// Collects `samples` readings, limited to the length of the buffer. The configuration is polled every
+    /// millisecond with the delay while waiting for a reading, returns [Error::Timeout] if a reading takes more than two
+    /// conversion cycles. The readings don't go through the calibration and observer
+    pub async fn measure_noise<D>(
+        &mut self,
+        samples: usize,
+        buf: &mut [i16],
+        delay: &mut D,
+    ) -> Result<NoiseStats, Error<E>>
+    where
+        D: DelayNs,
+    {
+        let samples = samples.min(buf.len());
+        let buf = &mut buf[..samples];
+        for sample in buf.iter_mut() {
+            self.tmp117
+                .wait_for_data_delay(
+                    delay,
+                    1,
+                    self.cycle_ms.saturating_mul(DATA_TIMEOUT_CYCLES),
+                    |_| {},
+                )
+                .await?;
+            *sample = self.tmp117.read_temp_raw_i16().await?;
+        }
+        Ok(NoiseStats::from_counts(buf))
+    }
+
+    /// Wait for the data to be ready and read the temperature in celsius, synchronized on the conversion cycle.
+    /// Also returns if samples were missed: more than one conversion cycle elapsed since the previous call according to the clock,
+    /// so the data ready flag was set more than once. The first call never reports an overrun.
+    /// Useful for signal processing where the samples must be uniform
+    pub async fn read_synced<C>(&mut self, clock: &mut C) -> Result<(Celsius, bool), Error<E>>
+    where
+        C: Clock,
+    {
+        self.tmp117.set_data_ready().await?;
+        self.tmp117.wait_for_data().await?;
+        let now = clock.now_ms();
+        let overrun = self.overrun(now);
+        let val = self.tmp117.read_temp_raw().await?;
+        Ok((val, overrun))
+    }
+
+    /// Read the temperature in celsius, without waiting, and estimate the milliseconds until the next conversion is ready.
+    /// The estimation uses the cycle time and the last time the data ready flag was seen set by this call or
+    /// [read_synced](Self::read_synced), the first call may return the full cycle time.
+    /// Useful to sleep precisely until the next sample
+    pub async fn read_temp_and_next_in<C>(
+        &mut self,
+        clock: &mut C,
+    ) -> Result<(Celsius, u32), Error<E>>
+    where
+        C: Clock,
+    {
+        let config = self.tmp117.read_config().await?;
+        let now = clock.now_ms();
+        if config.data_ready() {
+            self.last_sample_ms = Some(now);
+        }
+        let val = self.tmp117.read_temp_raw().await?;
+
+        let next_in = match self.last_sample_ms {
+            Some(last) => self.cycle_ms - now.wrapping_sub(last) % self.cycle_ms,
+            None => self.cycle_ms,
+        };
+        Ok((val, next_in))
     }
 
-    /// Wait for the data to be ready and read the temperature in celsius
-    pub async fn wait_temp(&mut self) -> Result<f32, Error<E>> {
-        let tmp117 = unsafe { &mut *self.tmp117 };
-        tmp117.set_data_ready().await?;
-        tmp117.wait_for_data().await?;
-        tmp117.read_temp_raw().await
+    fn overrun(&mut self, now: u32) -> bool {
+        // Allow half a cycle of jitter before considering a sample was missed
+        let overrun = self
+            .last_sample_ms
+            .is_some_and(|last| now.wrapping_sub(last) > self.cycle_ms + self.cycle_ms / 2);
+        self.last_sample_ms = Some(now);
+        overrun
+    }
+
+    /// Set the high limit in celsius while the conversions continue.
+    /// The limit is compared to the next conversion result, an alert already raised stays set until read
+    pub async fn set_high_limit(&mut self, high_c: f32) -> Result<(), Error<E>> {
+        self.tmp117.set_high_limit(high_c).await
+    }
+
+    /// Set the low limit in celsius while the conversions continue.
+    /// The limit is compared to the next conversion result, an alert already raised stays set until read
+    pub async fn set_low_limit(&mut self, low_c: f32) -> Result<(), Error<E>> {
+        self.tmp117.set_low_limit(low_c).await
+    }
+
+    /// Set the average while the conversions continue, the cycle time used by the synchronized reads is updated.
+    /// The conversion in progress completes with the previous average, so the next sample may still reflect it
+    pub async fn set_average(&mut self, average: Average) -> Result<(), Error<E>> {
+        self.tmp117.set_average(average).await?;
+        if let Some(config) = self.tmp117.config {
+            self.cycle_ms = config.conversion().cycle_time_us(average) / 1000;
+        }
+        Ok(())
+    }
+
+    /// Set the conversion cycle while the conversions continue, the cycle time used by the synchronized reads is updated.
+    /// The conversion in progress completes with the previous cycle
+    pub async fn set_conversion(&mut self, conversion: Conversion) -> Result<(), Error<E>> {
+        self.tmp117.set_conversion(conversion).await?;
+        if let Some(config) = self.tmp117.config {
+            self.cycle_ms = conversion.cycle_time_us(config.average()) / 1000;
+        }
+        Ok(())
+    }
+
+    /// Returns the [stats](SessionStats) of the temperatures read since the start of the session
+    pub fn stats(&self) -> SessionStats {
+        self.tmp117.session.stats()
     }
 
     /// Check if an alert was triggered since the last calll
     pub async fn get_alert(&mut self) -> Result<Alert, Error<E>> {
-        let tmp117 = unsafe { &mut *self.tmp117 };
-        tmp117.check_alert().await
+        self.tmp117.poll_alert().await
     }
 
     /// Wait for an alert to come and return it's value
     pub async fn wait_alert(&mut self) -> Result<Alert, Error<E>> {
-        let tmp117 = unsafe { &mut *self.tmp117 };
-        tmp117.set_alert().await?;
-        tmp117.wait_for_alert().await
+        self.tmp117.set_alert().await?;
+        self.tmp117.wait_for_alert().await
+    }
+
+    /// Same as [wait_temp](Self::wait_temp) but returns [Error::Timeout] if the data is not ready after `timeout_ms`.
+    /// The configuration is polled with the delay, the alert pin is not used
+    pub async fn try_wait_temp<D>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<Celsius, Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.tmp117
+            .wait_for_data_timeout(delay, timeout_ms.saturating_mul(1000))
+            .await?;
+        self.tmp117.read_temp_raw().await
+    }
+
+    /// Same as [wait_alert](Self::wait_alert) but returns [Error::Timeout] if no alert came after `timeout_ms`.
+    /// The configuration is polled with the delay, the alert pin is not used
+    pub async fn try_wait_alert<D>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<Alert, Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.tmp117
+            .wait_for_alert_timeout(delay, timeout_ms.saturating_mul(1000))
+            .await
+    }
+
+    /// Returns the temperatures as a stream, every call to [next](Readings::next) does a [wait_temp](Self::wait_temp).
+    /// The stream never ends, stop calling `next` to stop. The stream borrows the handler,
+    /// so it can still be returned from the [continuous](Tmp117::continuous) closure once done
+    pub fn readings(&mut self) -> Readings<'_, 'a, ADDR, T, E, P, O, K> {
+        Readings { handler: self }
+    }
+}
+
+/// Stream of the temperatures of the continuous mode, see [ContinuousHandler::readings]
+pub struct Readings<'h, 'a, const ADDR: u8, T, E, P, O = fn(f32), K = fn(f32) -> f32> {
+    handler: &'h mut ContinuousHandler<'a, ADDR, T, E, P, O, K>,
+}
+
+impl<'h, 'a, const ADDR: u8, T, E, P, O, K> Readings<'h, 'a, ADDR, T, E, P, O, K>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: Wait,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    /// Wait for the next temperature, never returns `None`
+    pub async fn next(&mut self) -> Option<Result<Celsius, Error<E>>> {
+        Some(self.handler.wait_temp().await)
     }
 }
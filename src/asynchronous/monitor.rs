@@ -0,0 +1,54 @@
+//! Async debugging utility reporting the registers changing over time
+use embedded_hal::i2c::SevenBitAddress;
+use embedded_hal_async::i2c::I2c;
+
+use super::tmp117_ll::Tmp117LL;
+use crate::error::ErrorLL;
+use crate::register::RegisterId;
+
+/// Reads a set of registers on every poll and reports the ones that changed since the previous poll.
+/// Note that reading the [Temperature](crate::register::Temperature) or the [Configuration](crate::register::Configuration)
+/// registers clears the `data_ready` and alert flags, monitoring them can interfere with the driver.
+pub struct RegisterMonitor<'a, const ADDR: u8, T, E, F, const N: usize> {
+    tmp_ll: &'a mut Tmp117LL<ADDR, T, E>,
+    registers: [RegisterId; N],
+    values: [Option<u16>; N],
+    callback: F,
+}
+
+impl<'a, const ADDR: u8, T, E, F, const N: usize> RegisterMonitor<'a, ADDR, T, E, F, N>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error,
+    F: FnMut(RegisterId, u16, u16),
+{
+    /// Creates a monitor of the registers, the callback is called with the register, the old and the new value
+    pub fn new(
+        tmp_ll: &'a mut Tmp117LL<ADDR, T, E>,
+        registers: [RegisterId; N],
+        callback: F,
+    ) -> Self {
+        Self {
+            tmp_ll,
+            registers,
+            values: [None; N],
+            callback,
+        }
+    }
+
+    /// Read the registers and call the callback for every one that changed since the last poll.
+    /// The first poll only records the values. A single transfer buffer is used for all the registers
+    /// to keep the stack, and the future in async, small regardless of the number of registers
+    pub async fn poll(&mut self) -> Result<(), ErrorLL<E>> {
+        let mut buff = [0; 2];
+        for (id, value) in self.registers.iter().zip(self.values.iter_mut()) {
+            let new = self.tmp_ll.read_raw_with(id.address(), &mut buff).await?;
+            if let Some(old) = value.replace(new) {
+                if old != new {
+                    (self.callback)(*id, old, new);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+//! Async thermostat built on the thermal mode of the device
+use embedded_hal::i2c::SevenBitAddress;
+use embedded_hal_async::{digital::Wait, i2c::I2c};
+
+use super::{ContinuousHandler, Tmp117};
+use crate::{thermostat::thermostat_config, ContinuousConfig, Error};
+
+pub use crate::thermostat::ThermostatState;
+
+/// Heating thermostat with hysteresis using the thermal mode of the device, see [TriggerMode::Thermal](crate::register::TriggerMode::Thermal).
+/// The device runs in continuous mode until the thermostat is [stopped](Thermostat::stop), like the handler nothing shuts the device down
+/// if it is dropped without being stopped.
+/// The alert flags are polled, the alert pin is not used
+pub struct Thermostat<'a, const ADDR: u8, T, E, P, O = fn(f32), K = fn(f32) -> f32> {
+    handler: ContinuousHandler<'a, ADDR, T, E, P, O, K>,
+}
+
+impl<'a, const ADDR: u8, T, E, P, O, K> Thermostat<'a, ADDR, T, E, P, O, K>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: Wait,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    /// Configure the limits and the thermal mode and start the continuous conversions with the average and conversion of the config.
+    /// The high limit is the setpoint and the low limit the setpoint minus the hysteresis, both in celsius.
+    /// Returns [Error::InvalidLimits] if the hysteresis is negative and [Error::OutOfRange] if a limit is outside of the registers range
+    pub async fn new(
+        tmp117: &'a mut Tmp117<ADDR, T, E, P, O, K>,
+        config: ContinuousConfig,
+        setpoint_c: f32,
+        hysteresis_c: f32,
+    ) -> Result<Self, Error<E>> {
+        let config = thermostat_config(config, setpoint_c, hysteresis_c)?;
+        let handler = tmp117.start_continuous(config).await?;
+        Ok(Self { handler })
+    }
+
+    /// Read the latched high alert flag and return the state of the thermostat.
+    /// Note that reading the configuration clears the data ready flag
+    pub async fn poll(&mut self) -> Result<ThermostatState, Error<E>> {
+        let alert = self.handler.tmp117.poll_alert().await?;
+        Ok(alert.into())
+    }
+
+    /// Stop the thermostat and put the device in shutdown
+    pub async fn stop(self) -> Result<(), Error<E>> {
+        self.handler.stop().await
+    }
+}
@@ -1,5 +1,9 @@
 //! Errors used for the driver
 
+use core::fmt;
+
+use crate::CelsiusOutOfRange;
+
 /// Error emitted by the TMP117 drivers
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -15,6 +19,42 @@ pub enum Error<E> {
 
     /// Received Invalid data
     InvalidData,
+
+    /// The device didn't respond in the allowed time
+    Timeout,
+
+    /// The low limit is higher than the high limit
+    InvalidLimits,
+
+    /// The device is not in the conversion mode expected by the operation
+    WrongMode,
+
+    /// The soft reset bit is set, the device is in the middle of a reset
+    ResetInProgress,
+
+    /// The conversion mode bits hold the reserved value, contains the raw bits of the mode
+    InvalidMode(u8),
+
+    /// The EEPROM stayed busy longer than the power-up EEPROM load
+    EepromBusy,
+
+    /// The register address is outside of the register map of the device, contains the address
+    InvalidRegister(u8),
+
+    /// The temperature is outside of the –256 °C to 255.99 °C range of the registers
+    OutOfRange,
+
+    /// The user EEPROM index is not 0, 1 or 2, or is 0 where the NIST traceability ID is protected, contains the index
+    InvalidIndex(u8),
+
+    /// The device ID is not the one of a TMP117, the bus is likely miswired or the address wrong
+    WrongDevice {
+        /// The device ID read
+        found: u16,
+    },
+
+    /// The temperature register still holds the -256 °C power-up value and no conversion completed
+    NotYetConverted,
 }
 
 /// Error emitted by the low level TMP117 drivers
@@ -36,3 +76,174 @@ impl<E> From<ErrorLL<E>> for Error<E> {
         }
     }
 }
+
+impl<E> From<CelsiusOutOfRange> for Error<E> {
+    fn from(_: CelsiusOutOfRange) -> Self {
+        Error::OutOfRange
+    }
+}
+
+/// [Error] without the bus error type, for code that only needs to know what failed,
+/// e.g. storing the errors of drivers on different buses in the same type. See [Error::erase]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum SimpleError {
+    /// Internal i2c bus error, the bus error itself is dropped
+    Bus,
+
+    /// Data is not ready
+    DataNotReady,
+
+    /// Alert pin error
+    AlertPin,
+
+    /// Received Invalid data
+    InvalidData,
+
+    /// The device didn't respond in the allowed time
+    Timeout,
+
+    /// The low limit is higher than the high limit
+    InvalidLimits,
+
+    /// The device is not in the conversion mode expected by the operation
+    WrongMode,
+
+    /// The soft reset bit is set, the device is in the middle of a reset
+    ResetInProgress,
+
+    /// The conversion mode bits hold the reserved value, contains the raw bits of the mode
+    InvalidMode(u8),
+
+    /// The EEPROM stayed busy longer than the power-up EEPROM load
+    EepromBusy,
+
+    /// The register address is outside of the register map of the device, contains the address
+    InvalidRegister(u8),
+
+    /// The temperature is outside of the –256 °C to 255.99 °C range of the registers
+    OutOfRange,
+
+    /// The user EEPROM index is not 0, 1 or 2, or is 0 where the NIST traceability ID is protected, contains the index
+    InvalidIndex(u8),
+
+    /// The device ID is not the one of a TMP117, the bus is likely miswired or the address wrong
+    WrongDevice {
+        /// The device ID read
+        found: u16,
+    },
+
+    /// The temperature register still holds the -256 °C power-up value and no conversion completed
+    NotYetConverted,
+}
+
+impl<E> Error<E> {
+    /// Drop the bus error and keep only the kind of error
+    pub fn erase(self) -> SimpleError {
+        self.kind()
+    }
+
+    fn kind(&self) -> SimpleError {
+        match *self {
+            Error::Bus(_) => SimpleError::Bus,
+            Error::DataNotReady => SimpleError::DataNotReady,
+            Error::AlertPin => SimpleError::AlertPin,
+            Error::InvalidData => SimpleError::InvalidData,
+            Error::Timeout => SimpleError::Timeout,
+            Error::InvalidLimits => SimpleError::InvalidLimits,
+            Error::WrongMode => SimpleError::WrongMode,
+            Error::ResetInProgress => SimpleError::ResetInProgress,
+            Error::InvalidMode(mode) => SimpleError::InvalidMode(mode),
+            Error::EepromBusy => SimpleError::EepromBusy,
+            Error::InvalidRegister(addr) => SimpleError::InvalidRegister(addr),
+            Error::OutOfRange => SimpleError::OutOfRange,
+            Error::InvalidIndex(index) => SimpleError::InvalidIndex(index),
+            Error::WrongDevice { found } => SimpleError::WrongDevice { found },
+            Error::NotYetConverted => SimpleError::NotYetConverted,
+        }
+    }
+}
+
+impl<E> From<Error<E>> for SimpleError {
+    fn from(value: Error<E>) -> Self {
+        value.erase()
+    }
+}
+
+impl fmt::Display for SimpleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimpleError::Bus => write!(f, "i2c bus error"),
+            SimpleError::DataNotReady => write!(f, "temperature data not ready"),
+            SimpleError::AlertPin => write!(f, "alert pin error"),
+            SimpleError::InvalidData => write!(f, "received invalid data"),
+            SimpleError::Timeout => write!(f, "the device didn't respond in time"),
+            SimpleError::InvalidLimits => write!(f, "the low limit is higher than the high limit"),
+            SimpleError::WrongMode => {
+                write!(f, "the device is not in the expected conversion mode")
+            }
+            SimpleError::ResetInProgress => write!(f, "a soft reset is in progress"),
+            SimpleError::InvalidMode(mode) => write!(f, "reserved conversion mode {mode:#04b}"),
+            SimpleError::EepromBusy => write!(f, "the EEPROM is busy"),
+            SimpleError::InvalidRegister(addr) => {
+                write!(f, "register {addr:#04x} is outside of the register map")
+            }
+            SimpleError::OutOfRange => {
+                write!(f, "temperature outside of the range of the registers")
+            }
+            SimpleError::InvalidIndex(index) => {
+                write!(f, "user EEPROM index {index} is invalid or protected")
+            }
+            SimpleError::WrongDevice { found } => {
+                write!(f, "device ID {found:#06x} is not a tmp117")
+            }
+            SimpleError::NotYetConverted => write!(f, "no conversion completed since power-up"),
+        }
+    }
+}
+
+impl<E> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind().fmt(f)
+    }
+}
+
+impl<E> fmt::Display for ErrorLL<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorLL::Bus(_) => write!(f, "i2c bus error"),
+            ErrorLL::InvalidData => write!(f, "received invalid data"),
+        }
+    }
+}
+
+/// The bus error is the [source](core::error::Error::source) of [Error::Bus], it is not part of the message
+#[cfg(feature = "error-in-core")]
+impl<E> core::error::Error for Error<E>
+where
+    E: core::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Error::Bus(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// The bus error is the [source](core::error::Error::source) of [ErrorLL::Bus], it is not part of the message
+#[cfg(feature = "error-in-core")]
+impl<E> core::error::Error for ErrorLL<E>
+where
+    E: core::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            ErrorLL::Bus(e) => Some(e),
+            ErrorLL::InvalidData => None,
+        }
+    }
+}
+
+#[cfg(feature = "error-in-core")]
+impl core::error::Error for SimpleError {}
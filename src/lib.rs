@@ -2,287 +2,2603 @@
 #![no_std]
 #![deny(missing_docs)]
 
+use core::{
+    convert::Infallible,
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
 use device_register::{EditRegister, ReadRegister, WriteRegister};
 use embedded_hal::{
     delay::DelayNs,
+    digital::{ErrorType, InputPin},
     i2c::{I2c, SevenBitAddress},
 };
 pub use error::Error;
+use error::ErrorLL;
 use register::*;
 use tmp117_ll::Tmp117LL;
 
+#[cfg(feature = "embedded-hal-bus")]
+pub mod array;
 pub mod asynchronous;
 pub mod error;
+pub mod monitor;
 pub mod register;
+pub mod thermostat;
 pub mod tmp117_ll;
 
 /// Conversion factor used by the device. One lsb is this value
 pub const CELCIUS_CONVERSION: f32 = 0.0078125;
 
-/// The types of alerts possible
-pub enum Alert {
-    /// No alert were triggered
-    None,
+/// Lowest raw value of the temperature, limit and offset registers
+pub const TEMP_MIN_RAW: i16 = i16::MIN;
 
-    /// A high alert was triggered
-    High,
+/// Highest raw value of the temperature, limit and offset registers
+pub const TEMP_MAX_RAW: i16 = i16::MAX;
 
-    /// A low alert was triggered
-    Low,
+/// Lowest temperature representable by the registers, -256°C
+pub const TEMP_MIN_CELSIUS: f32 = TEMP_MIN_RAW as f32 * CELCIUS_CONVERSION;
 
-    /// A high and a low alert was triggered
-    HighLow,
-}
+/// Highest temperature representable by the registers, one lsb below 256°C
+pub const TEMP_MAX_CELSIUS: f32 = TEMP_MAX_RAW as f32 * CELCIUS_CONVERSION;
 
-/// The continuous config
-#[derive(Default)]
-pub struct ContinuousConfig {
-    /// The average used, will use the one stored in the register if None
-    pub average: Average,
+/// Maximum time waited for the EEPROM to be loaded after power-up, typically 1.5ms
+pub(crate) const POWER_UP_TIMEOUT_US: u32 = 10_000;
 
-    /// The convesion used, will use the one stored in the register if None
-    pub conversion: Conversion,
+/// Safe bound for an EEPROM write, which typically completes in 7ms
+pub const EEPROM_WRITE_TIMEOUT_MS: u32 = 50;
 
-    /// The high alert used, will use the one stored in the register if None
-    pub high: Option<f32>,
+/// Typical duration of a soft reset
+pub(crate) const RESET_DELAY_MS: u32 = 2;
 
-    /// The low alert used, will use the one stored in the register if None
-    pub low: Option<f32>,
+/// Soft reset bit of the configuration register, checked on the raw value before the mode is decoded
+pub(crate) const CONFIG_RESET_BIT: u16 = 1 << 1;
+
+/// Value of the temperature register until the first conversion completes, -256°C
+pub(crate) const POWER_UP_TEMPERATURE: u16 = 0x8000;
+
+/// Maximum time waited for the first conversion, the longest conversion with 64 averages
+pub(crate) const FIRST_CONVERSION_TIMEOUT_US: u32 = 1_000_000;
+
+/// Interval between polls of the device when waiting with a delay
+pub(crate) const POLL_INTERVAL_US: u32 = 100;
+
+/// Conversion cycles waited for the data with a delay before giving up, a single cycle can be missed
+pub(crate) const DATA_TIMEOUT_CYCLES: u32 = 2;
 
-    /// The temperature offset used, will use 0 if None
-    pub offset: Option<f32>,
+/// Converts a temperature in celsius to the two's complement representation used by the registers,
+/// fails if the temperature is outside of the range of the registers
+pub(crate) fn celsius_to_raw(val: f32) -> Result<u16, CelsiusOutOfRange> {
+    Ok(i16::try_from(Celsius(val))? as u16)
 }
-/// Represents the ID of the device.
+
+/// Decodes the raw configuration, returns [Error::InvalidMode] with the mode bits if they hold the reserved value
+pub(crate) fn decode_config<E>(raw: u16) -> Result<Configuration, Error<E>> {
+    Configuration::try_from(raw).map_err(|_| Error::InvalidMode((raw >> 10) as u8 & 0b11))
+}
+
+/// A temperature in celsius
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
-pub struct Id {
-    /// Should always be 0x117
-    pub device: u16,
-    /// Depends on the revision of the device
-    pub revision: u8,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, PartialOrd, Debug, Default)]
+pub struct Celsius(pub f32);
+
+impl Celsius {
+    /// Returns the temperature as a float in celsius
+    pub fn into_inner(self) -> f32 {
+        self.0
+    }
+
+    /// Clamps the temperature into the [TEMP_MIN_CELSIUS] to [TEMP_MAX_CELSIUS] range of the registers.
+    /// The `_clamped` setters like [set_high_limit_clamped](Tmp117::set_high_limit_clamped) use it,
+    /// the other setters return [Error::OutOfRange] instead
+    pub fn clamp_to_range(self) -> Celsius {
+        Celsius(self.0.clamp(TEMP_MIN_CELSIUS, TEMP_MAX_CELSIUS))
+    }
 }
 
-/// The TMP117 driver. Note that the alert pin is not used in this driver,
-/// see the async implementation if you want the driver to use the alert pin in the drive
-pub struct Tmp117<const ADDR: u8, T, E> {
-    tmp_ll: Tmp117LL<ADDR, T, E>,
+impl From<i16> for Celsius {
+    /// Converts two's complement counts of the registers to celsius
+    fn from(counts: i16) -> Self {
+        Celsius(scale_counts(counts as f32))
+    }
 }
 
-impl<const ADDR: u8, T, E> Tmp117<ADDR, T, E>
-where
-    T: I2c<SevenBitAddress, Error = E>,
-    E: embedded_hal::i2c::Error + Copy,
-{
-    /// Create a new tmp117 from a i2c bus
-    pub fn new(i2c: T) -> Self {
-        Tmp117::<ADDR, T, E> {
-            tmp_ll: Tmp117LL::new(i2c),
+/// The temperature is outside of the –256 °C to 255.99 °C range of the registers
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct CelsiusOutOfRange;
+
+impl TryFrom<Celsius> for i16 {
+    type Error = CelsiusOutOfRange;
+
+    /// Converts to the two's complement counts of the registers, truncating toward zero
+    fn try_from(value: Celsius) -> Result<Self, Self::Error> {
+        if (TEMP_MIN_CELSIUS..=TEMP_MAX_CELSIUS).contains(&value.0) {
+            Ok(celsius_to_counts(value.0) as i16)
+        } else {
+            Err(CelsiusOutOfRange)
         }
     }
+}
 
-    /// Create a new tmp117 from a low level tmp117 driver
-    pub fn new_from_ll(tmp_ll: Tmp117LL<ADDR, T, E>) -> Self {
-        Tmp117::<ADDR, T, E> { tmp_ll }
+impl fmt::Display for Celsius {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} °C", self.0)
     }
+}
 
-    /// Returns the ID of the device
-    pub fn id(&mut self) -> Result<Id, Error<E>> {
-        let id: DeviceID = self.tmp_ll.read()?;
-        Ok(Id {
-            device: id.device_id().into(),
-            revision: id.revision().into(),
-        })
+/// Converts the raw value of a temperature, limit or offset register to celsius, sign extending the two's complement
+pub fn counts_to_celsius(raw: u16) -> f32 {
+    Celsius::from(raw as i16).0
+}
+
+/// Scales counts of [CELCIUS_CONVERSION] to celsius, the counts can be fractional, e.g. the mean of several readings.
+/// Every conversion from counts goes through here
+pub(crate) fn scale_counts(counts: f32) -> f32 {
+    counts * CELCIUS_CONVERSION
+}
+
+/// Converts a temperature in celsius to the raw value of a temperature, limit or offset register, truncating toward zero.
+/// Temperatures outside of the –256 °C to 255.99 °C range saturate at the limits, use [`i16::try_from`] on a [Celsius]
+/// to detect them instead
+pub fn celsius_to_counts(celsius: f32) -> u16 {
+    (celsius / CELCIUS_CONVERSION) as i16 as u16
+}
+
+/// Converts a temperature in celsius to fahrenheit
+pub fn celsius_to_fahrenheit(celsius: f32) -> f32 {
+    celsius * 9.0 / 5.0 + 32.0
+}
+
+/// Converts a temperature in celsius to kelvin
+pub fn celsius_to_kelvin(celsius: f32) -> f32 {
+    celsius + 273.15
+}
+
+/// Converts raw temperature counts to hundredths of a degree fahrenheit without using floats.
+/// The result is rounded to the nearest hundredth, halfway values are rounded away from zero.
+/// With °F = counts × 9 / 640 + 32, the hundredths are (counts × 45 + 102400) / 32 which stays far within an i32
+/// for the whole i16 range
+pub fn fahrenheit_centi_from_counts(counts: i16) -> i32 {
+    let num = counts as i32 * 45 + 3200 * 32;
+    if num >= 0 {
+        (num + 16) / 32
+    } else {
+        (num - 16) / 32
     }
+}
 
-    fn wait_eeprom(&mut self) -> Result<(), Error<E>> {
-        let mut configuration: Configuration = self.tmp_ll.read()?;
-        while configuration.eeprom_busy() {
-            configuration = self.tmp_ll.read()?;
-        }
+/// A temperature type built from the two's complement counts of the temperature register,
+/// used by the generic reads such as [oneshot_as](Tmp117::oneshot_as) to return any unit type.
+/// Implemented for `f32` and [Celsius] in celsius, and for the `uom` thermodynamic temperature with the `uom` feature
+pub trait FromRawTemperature {
+    /// Converts the counts of [CELCIUS_CONVERSION] to the temperature
+    fn from_raw(counts: i16) -> Self;
+}
 
-        Ok(())
+impl FromRawTemperature for f32 {
+    fn from_raw(counts: i16) -> Self {
+        counts_to_celsius(counts as u16)
     }
+}
 
-    fn read_temp_raw(&mut self) -> Result<f32, Error<E>> {
-        let temp: Temperature = self.tmp_ll.read()?;
+impl FromRawTemperature for Celsius {
+    fn from_raw(counts: i16) -> Self {
+        Celsius::from(counts)
+    }
+}
 
-        // Convert to i16 for two complements
-        let val = (u16::from(temp) as i16) as f32 * CELCIUS_CONVERSION;
-        Ok(val)
+#[cfg(feature = "uom")]
+impl FromRawTemperature for uom::si::f32::ThermodynamicTemperature {
+    fn from_raw(counts: i16) -> Self {
+        Self::new::<uom::si::thermodynamic_temperature::degree_celsius>(f32::from_raw(counts))
     }
+}
 
-    fn check_alert(&mut self) -> Result<Alert, Error<E>> {
-        let config: Configuration = self.tmp_ll.read()?;
-        if config.high_alert() && config.low_alert() {
-            Ok(Alert::HighLow)
-        } else if config.high_alert() {
-            Ok(Alert::High)
-        } else if config.low_alert() {
-            Ok(Alert::Low)
-        } else {
-            Ok(Alert::None)
+/// Delay that doesn't wait, used by the operations taking an optional delay when none is given
+pub(crate) struct NoDelay;
+
+impl DelayNs for NoDelay {
+    fn delay_ns(&mut self, _: u32) {}
+}
+
+impl embedded_hal_async::delay::DelayNs for NoDelay {
+    async fn delay_ns(&mut self, _: u32) {}
+}
+
+/// Address of the user EEPROM register at the index
+pub(crate) fn user_eeprom_address<E>(index: u8) -> Result<u8, Error<E>> {
+    let id = match index {
+        0 => RegisterId::UEEPROM1,
+        1 => RegisterId::UEEPROM2,
+        2 => RegisterId::UEEPROM3,
+        _ => return Err(Error::InvalidIndex(index)),
+    };
+    Ok(id.address())
+}
+
+/// I2C addresses of the device, selected by the pin the ADD0 pin is connected to.
+/// Use it as the `ADDR` of the driver with `Tmp117::<{ I2cAddress::Gnd as u8 }, _, _>`,
+/// or with [new_with_address](Tmp117::new_with_address) when the address is only known at runtime
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum I2cAddress {
+    /// ADD0 connected to ground
+    Gnd = 0x48,
+
+    /// ADD0 connected to V+
+    Vcc = 0x49,
+
+    /// ADD0 connected to SDA
+    Sda = 0x4A,
+
+    /// ADD0 connected to SCL
+    Scl = 0x4B,
+}
+
+impl I2cAddress {
+    /// Every address of the device, in address order
+    pub const ALL: [I2cAddress; 4] = [
+        I2cAddress::Gnd,
+        I2cAddress::Vcc,
+        I2cAddress::Sda,
+        I2cAddress::Scl,
+    ];
+
+    /// Returns the address matching the raw 7 bits address, if it is one the device can answer at
+    pub fn from_u8(addr: u8) -> Option<Self> {
+        match addr {
+            0x48 => Some(I2cAddress::Gnd),
+            0x49 => Some(I2cAddress::Vcc),
+            0x4A => Some(I2cAddress::Sda),
+            0x4B => Some(I2cAddress::Scl),
+            _ => None,
         }
     }
+}
 
-    fn wait_for_data(&mut self) -> Result<(), Error<E>> {
-        // Loop while the data is not ok
-        loop {
-            let config: Configuration = self.tmp_ll.read()?;
-            if config.data_ready() {
-                break;
-            }
-        }
-        Ok(())
+/// `ADDR` of the drivers created with [new_with_address](Tmp117::new_with_address), the address is only known at runtime.
+/// `new` doesn't build with it since it isn't an address of the device
+pub const RUNTIME_ADDRESS: u8 = 0;
+
+/// Compile time check of the `ADDR` given to `new`
+pub(crate) struct AddressCheck<const ADDR: u8>;
+
+impl<const ADDR: u8> AddressCheck<ADDR> {
+    /// Fails the build if the `ADDR` is not one of the [I2cAddress], e.g. 0 which is the general call address
+    pub(crate) const VALID: () = assert!(
+        matches!(ADDR, 0x48..=0x4B),
+        "the ADDR of the driver must be an I2cAddress"
+    );
+}
+
+impl From<I2cAddress> for u8 {
+    fn from(addr: I2cAddress) -> Self {
+        addr as u8
     }
+}
 
-    fn wait_for_alert(&mut self) -> Result<Alert, Error<E>> {
-        loop {
-            let alert = self.check_alert();
-            if let Ok(Alert::None) = alert {
-                continue;
-            } else {
-                return alert;
-            }
+/// The types of alerts possible
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Alert {
+    /// No alert were triggered
+    None,
+
+    /// A high alert was triggered
+    High,
+
+    /// A low alert was triggered
+    Low,
+
+    /// A high and a low alert was triggered
+    HighLow,
+}
+
+impl From<Configuration> for Alert {
+    fn from(config: Configuration) -> Self {
+        match (config.high_alert(), config.low_alert()) {
+            (true, true) => Alert::HighLow,
+            (true, false) => Alert::High,
+            (false, true) => Alert::Low,
+            (false, false) => Alert::None,
         }
     }
+}
 
-    fn set_continuous(
-        &mut self,
-        config: ContinuousConfig,
-    ) -> Result<ContinuousHandler<'_, ADDR, T, E>, Error<E>> {
-        if let Some(val) = config.high {
-            let high: HighLimit = ((val / CELCIUS_CONVERSION) as u16).into();
-            self.tmp_ll.write(high)?;
-        }
-        if let Some(val) = config.low {
-            let low: LowLimit = ((val / CELCIUS_CONVERSION) as u16).into();
-            self.tmp_ll.write(low)?;
-        }
-        if let Some(val) = config.offset {
-            let off: TemperatureOffset = ((val / CELCIUS_CONVERSION) as u16).into();
-            self.tmp_ll.write(off)?;
-        }
+/// A temperature read along with the flags of the configuration read before it
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Measurement {
+    /// The temperature
+    pub celsius: Celsius,
 
-        self.tmp_ll.edit(|r: &mut Configuration| {
-            r.set_mode(ConversionMode::Continuous);
-            r.set_polarity(Polarity::ActiveLow);
-            r.set_average(config.average);
-            r.set_conversion(config.conversion);
-        })?;
+    /// The alerts triggered since the last read of the configuration
+    pub alert: Alert,
+
+    /// If the temperature is a new conversion since the last read of the configuration
+    pub data_ready: bool,
+}
+
+/// A compact fixed size frame of a reading, e.g. to send over a radio link.
+/// The encoding is hand rolled so both ends agree on it without a serialization library:
+/// the raw temperature in big endian followed by the flags
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct MeasurementFrame {
+    /// Two's complement counts of [CELCIUS_CONVERSION]
+    pub raw_temp: i16,
+
+    /// The data ready, high alert and low alert flags, see the `FLAG_` constants
+    pub flags: u8,
+}
+
+impl MeasurementFrame {
+    /// The temperature is a new conversion
+    pub const FLAG_DATA_READY: u8 = 1 << 0;
+
+    /// A high alert was triggered
+    pub const FLAG_HIGH_ALERT: u8 = 1 << 1;
+
+    /// A low alert was triggered
+    pub const FLAG_LOW_ALERT: u8 = 1 << 2;
 
-        Ok(ContinuousHandler { tmp117: self })
+    /// Encode the frame
+    pub fn to_bytes(&self) -> [u8; 3] {
+        let [msb, lsb] = self.raw_temp.to_be_bytes();
+        [msb, lsb, self.flags]
     }
 
-    fn set_oneshot(&mut self, average: Average) -> Result<(), Error<E>> {
-        let val = self.tmp_ll.edit(|r: &mut Configuration| {
-            r.set_mode(ConversionMode::OneShot);
-            r.set_polarity(Polarity::ActiveLow);
-            r.set_average(average);
-        })?;
-        Ok(val)
+    /// Decode a frame encoded by [to_bytes](Self::to_bytes)
+    pub fn from_bytes(bytes: [u8; 3]) -> Self {
+        Self {
+            raw_temp: i16::from_be_bytes([bytes[0], bytes[1]]),
+            flags: bytes[2],
+        }
     }
 
-    fn set_shutdown(&mut self) -> Result<(), Error<E>> {
-        let val = self.tmp_ll.edit(|r: &mut Configuration| {
-            r.set_mode(ConversionMode::Shutdown);
-        })?;
-        Ok(val)
+    /// Builds the frame from the counts and the flags of the configuration read before them
+    pub(crate) fn new(raw_temp: i16, config: Configuration) -> Self {
+        let mut flags = 0;
+        if config.data_ready() {
+            flags |= Self::FLAG_DATA_READY;
+        }
+        if config.high_alert() {
+            flags |= Self::FLAG_HIGH_ALERT;
+        }
+        if config.low_alert() {
+            flags |= Self::FLAG_LOW_ALERT;
+        }
+        Self { raw_temp, flags }
     }
+}
 
-    /// Resets the device and put it in shutdown
-    pub fn reset<D>(&mut self, delay: &mut D) -> Result<(), Error<E>>
-    where
-        D: DelayNs,
-    {
-        self.tmp_ll.edit(|r: &mut Configuration| {
-            r.set_reset(true);
-        })?;
-        delay.delay_ms(2);
-        self.set_shutdown()?;
-        Ok(())
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Alert::None => write!(f, "none"),
+            Alert::High => write!(f, "high"),
+            Alert::Low => write!(f, "low"),
+            Alert::HighLow => write!(f, "high and low"),
+        }
     }
+}
 
-    /// Write data to user eeprom. Note that this is blocking because we wait for write on the eeprom to complete
-    pub fn write_eeprom(&mut self, values: [u16; 3]) -> Result<(), Error<E>> {
-        self.wait_eeprom()?;
-        self.tmp_ll.write(UEEPROM1::from(values[0]))?;
+/// A monotonic clock in milliseconds, typically backed by a hardware timer.
+/// Used by the reads synchronized on the conversion cycle of the device
+pub trait Clock {
+    /// Returns the current time in milliseconds, it is allowed to wrap around
+    fn now_ms(&mut self) -> u32;
+}
 
-        self.wait_eeprom()?;
-        self.tmp_ll.write(UEEPROM2::from(values[1]))?;
+/// Status of a temperature reading, the register saturates at the limits of its range
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TempStatus {
+    /// The temperature is within the range of the register
+    Normal,
 
-        self.wait_eeprom()?;
-        self.tmp_ll.write(UEEPROM3::from(values[2]))?;
+    /// The temperature is pinned at the maximum of the register, 0x7FFF
+    SaturatedHigh,
 
-        Ok(())
+    /// The temperature is pinned at the minimum of the register, 0x8000
+    SaturatedLow,
+}
+
+impl From<i16> for TempStatus {
+    fn from(counts: i16) -> Self {
+        match counts {
+            i16::MAX => TempStatus::SaturatedHigh,
+            i16::MIN => TempStatus::SaturatedLow,
+            _ => TempStatus::Normal,
+        }
     }
+}
 
-    /// Read the data from the eeprom
-    pub fn read_eeprom(&mut self) -> Result<[u16; 3], Error<E>> {
-        let u1: UEEPROM1 = self.tmp_ll.read()?;
-        let u2: UEEPROM2 = self.tmp_ll.read()?;
-        let u3: UEEPROM3 = self.tmp_ll.read()?;
+/// Exponential moving average over successive readings, a low latency software smoothing
+/// complementing the hardware averaging. Independent of the bus, see [Tmp117::read_filtered]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct OneShotFilter {
+    /// Weight of a new reading between 0 and 1, higher follows the readings faster
+    pub alpha: f32,
 
-        Ok([u1.into(), u2.into(), u3.into()])
-    }
+    /// The filtered value, `None` until the first reading
+    pub state: Option<f32>,
+}
 
-    /// Wait for data and read the temperature in celsius and shutdown since it's a oneshot
-    pub fn oneshot(&mut self, average: Average) -> Result<f32, Error<E>> {
-        self.set_oneshot(average)?;
-        self.wait_for_data()?;
-        let data = self.read_temp_raw()?;
-        Ok(data)
+impl OneShotFilter {
+    /// Creates an empty filter with the weight of new readings
+    pub fn new(alpha: f32) -> Self {
+        Self { alpha, state: None }
     }
 
-    /// Pass a config and closure for the continuous mode.
-    /// The device gets set to continuous, then the function is called with the handler
-    /// and finally the device is shutdown
-    pub fn continuous<F>(&mut self, config: ContinuousConfig, f: F) -> Result<(), Error<E>>
-    where
-        F: FnOnce(ContinuousHandler<'_, ADDR, T, E>) -> Result<(), Error<E>>,
-    {
-        let handler = self.set_continuous(config)?;
-        f(handler)?;
-        self.set_shutdown()
+    /// Feed a reading to the filter and return the filtered value.
+    /// The first reading initializes the filter
+    pub fn update(&mut self, raw: f32) -> f32 {
+        let val = match self.state {
+            Some(prev) => prev + self.alpha * (raw - prev),
+            None => raw,
+        };
+        self.state = Some(val);
+        val
     }
 }
 
-/// Handler for the continuous mode
-pub struct ContinuousHandler<'a, const ADDR: u8, T, E> {
-    tmp117: &'a mut Tmp117<ADDR, T, E>,
+/// Noise statistics of successive readings, in raw counts of [CELCIUS_CONVERSION]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct NoiseStats {
+    /// Number of readings
+    pub count: usize,
+
+    /// Minimum reading
+    pub min: i16,
+
+    /// Maximum reading
+    pub max: i16,
+
+    /// Difference between the maximum and the minimum
+    pub peak_to_peak: u16,
+
+    /// Mean of the readings, rounded
+    pub mean: i16,
+
+    /// Variance of the readings around the rounded mean, in counts squared
+    pub variance: u32,
 }
 
-impl<'a, const ADDR: u8, T, E> ContinuousHandler<'a, ADDR, T, E>
-where
-    T: I2c<SevenBitAddress, Error = E>,
-    E: embedded_hal::i2c::Error + Copy,
-{
-    /// Read the temperature in celsius, return an error if the value of the temperature is not ready
-    pub fn read_temp(&mut self) -> Result<f32, Error<E>> {
-        let config: Configuration = self.tmp117.tmp_ll.read()?;
-        if !config.data_ready() {
-            return Err(Error::DataNotReady);
+impl NoiseStats {
+    /// Computes the statistics of the readings without floating point
+    pub fn from_counts(counts: &[i16]) -> Self {
+        let Some((&first, _)) = counts.split_first() else {
+            return Self::default();
+        };
+        let n = counts.len() as i64;
+        let (mut min, mut max, mut sum) = (first, first, 0i64);
+        for &c in counts {
+            min = min.min(c);
+            max = max.max(c);
+            sum += c as i64;
         }
+        // Round to the nearest for both signs
+        let mean = (2 * sum + sum.signum() * n) / (2 * n);
+        let squares: i64 = counts.iter().map(|&c| (c as i64 - mean).pow(2)).sum();
 
-        let val = self.tmp117.read_temp_raw()?;
-        Ok(val)
+        Self {
+            count: counts.len(),
+            min,
+            max,
+            peak_to_peak: max.abs_diff(min),
+            mean: mean as i16,
+            variance: (squares / n) as u32,
+        }
     }
+}
 
-    /// Wait for the data to be ready and read the temperature in celsius
-    pub fn wait_temp(&mut self) -> Result<f32, Error<E>> {
-        self.tmp117.wait_for_data()?;
-        let val = self.tmp117.read_temp_raw()?;
-        Ok(val)
+/// Summary of the temperatures read during a continuous session, in celsius.
+/// The minimum, maximum and mean are 0 if no temperature was read
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct SessionStats {
+    /// Minimum temperature
+    pub min: f32,
+
+    /// Maximum temperature
+    pub max: f32,
+
+    /// Mean of the temperatures
+    pub mean: f32,
+
+    /// Number of temperatures read
+    pub count: u32,
+}
+
+/// Accumulates the readings of a session in counts, only converted to celsius when the stats are requested
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct SessionAccumulator {
+    min: i16,
+    max: i16,
+    sum: i64,
+    count: u32,
+}
+
+impl SessionAccumulator {
+    pub(crate) fn record(&mut self, counts: i16) {
+        if self.count == 0 {
+            self.min = counts;
+            self.max = counts;
+        }
+        self.min = self.min.min(counts);
+        self.max = self.max.max(counts);
+        self.sum += counts as i64;
+        self.count += 1;
     }
 
-    /// Check if an alert was triggered since the last calll
-    pub fn get_alert(&mut self) -> Result<Alert, Error<E>> {
-        let val = self.tmp117.check_alert()?;
-        Ok(val)
+    pub(crate) fn stats(&self) -> SessionStats {
+        if self.count == 0 {
+            return SessionStats::default();
+        }
+        SessionStats {
+            min: counts_to_celsius(self.min as u16),
+            max: counts_to_celsius(self.max as u16),
+            mean: scale_counts(self.sum as f32 / self.count as f32),
+            count: self.count,
+        }
     }
+}
 
-    /// Wait for an alert to come and return it's value
-    pub fn wait_alert(&mut self) -> Result<Alert, Error<E>> {
-        let val = self.tmp117.wait_for_alert()?;
-        Ok(val)
+/// The continuous config.
+/// The device is left untouched and [Error::OutOfRange] is returned if a limit or the offset is outside of the registers range
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct ContinuousConfig {
+    /// The average used, will use the one stored in the register if None
+    pub average: Average,
+
+    /// The convesion used, will use the one stored in the register if None
+    pub conversion: Conversion,
+
+    /// The high alert used, will use the one stored in the register if None
+    pub high: Option<Celsius>,
+
+    /// The low alert used, will use the one stored in the register if None
+    pub low: Option<Celsius>,
+
+    /// The temperature offset used, will use the one stored in the register if None
+    pub offset: Option<Celsius>,
+
+    /// The polarity of the alert pin
+    pub polarity: Polarity,
+
+    /// The alert or thermal mode used, will use the one stored in the register if None.
+    /// Set it to [TriggerMode::Alert] to make sure a device previously in thermal mode goes back to alert mode
+    pub trigger_mode: Option<TriggerMode>,
+}
+
+impl ContinuousConfig {
+    /// Returns a [builder](ContinuousConfigBuilder) starting from the default config
+    pub fn builder() -> ContinuousConfigBuilder {
+        ContinuousConfigBuilder::default()
+    }
+}
+
+/// Builder of a [ContinuousConfig], the optional settings are only set when their method is called
+/// so the registers are left untouched otherwise
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct ContinuousConfigBuilder {
+    config: ContinuousConfig,
+}
+
+impl ContinuousConfigBuilder {
+    /// Set the average
+    pub fn average(mut self, average: Average) -> Self {
+        self.config.average = average;
+        self
+    }
+
+    /// Set the conversion cycle
+    pub fn conversion(mut self, conversion: Conversion) -> Self {
+        self.config.conversion = conversion;
+        self
+    }
+
+    /// Set the high limit in celsius
+    pub fn high_limit(mut self, high_c: f32) -> Self {
+        self.config.high = Some(Celsius(high_c));
+        self
+    }
+
+    /// Set the low limit in celsius
+    pub fn low_limit(mut self, low_c: f32) -> Self {
+        self.config.low = Some(Celsius(low_c));
+        self
+    }
+
+    /// Set the temperature offset in celsius
+    pub fn offset(mut self, offset_c: f32) -> Self {
+        self.config.offset = Some(Celsius(offset_c));
+        self
+    }
+
+    /// Set the polarity of the alert pin
+    pub fn polarity(mut self, polarity: Polarity) -> Self {
+        self.config.polarity = polarity;
+        self
+    }
+
+    /// Set the alert or thermal mode
+    pub fn trigger_mode(mut self, trigger_mode: TriggerMode) -> Self {
+        self.config.trigger_mode = Some(trigger_mode);
+        self
+    }
+
+    /// Returns the config
+    pub fn build(self) -> ContinuousConfig {
+        self.config
+    }
+}
+
+/// The settings of the device as read back from its registers
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Settings {
+    /// The conversion mode
+    pub mode: ConversionMode,
+
+    /// The average used
+    pub average: Average,
+
+    /// The conversion cycle used
+    pub conversion: Conversion,
+
+    /// The polarity of the alert pin
+    pub polarity: Polarity,
+
+    /// The alert or thermal mode
+    pub trigger_mode: TriggerMode,
+
+    /// The high limit in celsius
+    pub high: f32,
+
+    /// The low limit in celsius
+    pub low: f32,
+
+    /// The temperature offset in celsius
+    pub offset: f32,
+}
+
+/// Decoded configuration register, with the settings and the status flags
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ConfigSnapshot {
+    /// The conversion mode
+    pub mode: ConversionMode,
+
+    /// The average used
+    pub average: Average,
+
+    /// The conversion cycle used
+    pub conversion: Conversion,
+
+    /// The polarity of the alert pin
+    pub polarity: Polarity,
+
+    /// The alert or thermal mode
+    pub trigger_mode: TriggerMode,
+
+    /// What the alert pin reflects
+    pub dr_alert: AlertPinSelect,
+
+    /// The high alert flag
+    pub high_alert: bool,
+
+    /// The low alert flag
+    pub low_alert: bool,
+
+    /// The data ready flag
+    pub data_ready: bool,
+
+    /// The EEPROM busy flag
+    pub eeprom_busy: bool,
+}
+
+impl From<Configuration> for ConfigSnapshot {
+    fn from(config: Configuration) -> Self {
+        Self {
+            mode: config.mode(),
+            average: config.average(),
+            conversion: config.conversion(),
+            polarity: config.polarity(),
+            trigger_mode: config.trigger_mode(),
+            dr_alert: config.dr_alert(),
+            high_alert: config.high_alert(),
+            low_alert: config.low_alert(),
+            data_ready: config.data_ready(),
+            eeprom_busy: config.eeprom_busy(),
+        }
+    }
+}
+
+/// Represents the ID of the device.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Id {
+    /// Should always be [TMP117_DEVICE_ID](Id::TMP117_DEVICE_ID)
+    pub device: u16,
+    /// Depends on the revision of the device
+    pub revision: u8,
+}
+
+/// Revisions of the device documented by TI
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum KnownRevision {
+    /// First revision, the only one documented in the datasheet with the `0x0117` reset value of the device ID register
+    Rev0 = 0,
+}
+
+impl Id {
+    /// Expected value of [device](Id::device) for a TMP117
+    pub const TMP117_DEVICE_ID: u16 = 0x117;
+
+    /// Check that the device is a TMP117, the [revision](Id::revision) is not checked since it varies across silicon
+    pub fn is_tmp117(&self) -> bool {
+        self.device == Self::TMP117_DEVICE_ID
+    }
+
+    /// Returns the revision if it is a known one, useful to apply revision specific workarounds.
+    /// The raw value stays available in [revision](Id::revision)
+    pub fn revision_known(&self) -> Option<KnownRevision> {
+        match self.revision {
+            0 => Some(KnownRevision::Rev0),
+            _ => None,
+        }
+    }
+}
+
+/// Dummy type for the alert pin of a driver created without one, should never be used
+pub struct DummyPin(());
+impl ErrorType for DummyPin {
+    type Error = Infallible;
+}
+impl InputPin for DummyPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        unreachable!()
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        unreachable!()
+    }
+}
+
+/// The TMP117 driver. The alert pin is optional, see [new_with_alert](Tmp117::new_with_alert).
+/// Without it, the driver polls the configuration register instead of the level of the pin
+/// `O` and `K` are the types of the [read observer](Tmp117::set_read_observer) and of the [calibration](Tmp117::set_calibration),
+/// function pointers by default, see [with_read_observer](Tmp117::with_read_observer) to use a capturing closure
+pub struct Tmp117<const ADDR: u8, T, E, P = DummyPin, O = fn(f32), K = fn(f32) -> f32> {
+    tmp_ll: Tmp117LL<ADDR, T, E>,
+    alert: Option<P>,
+    observer: Option<O>,
+    calibration: Option<K>,
+    config: Option<Configuration>,
+    use_cache: bool,
+    shutdown_error: Option<Error<E>>,
+    session: SessionAccumulator,
+    oneshot_polarity: Polarity,
+}
+
+impl<T, E> Tmp117<RUNTIME_ADDRESS, T, E>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+{
+    /// Create a new tmp117 from a i2c bus with an address known at runtime, e.g. from a board config.
+    /// The `ADDR` of the driver is the [RUNTIME_ADDRESS] marker, so drivers with different addresses share the same type
+    pub fn new_with_address(i2c: T, address: I2cAddress) -> Self {
+        Self::new_from_ll(Tmp117LL::new_with_address(i2c, address))
+    }
+}
+
+impl<const ADDR: u8, T, E> Tmp117<ADDR, T, E>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+{
+    /// Create a new tmp117 from a i2c bus at the `ADDR` of the driver, written as `{ I2cAddress::Gnd as u8 }`
+    /// so only the addresses of the device are used. See [new_with_address](Tmp117::new_with_address) to pass the
+    /// [I2cAddress] directly
+    pub fn new(i2c: T) -> Self {
+        Tmp117::<ADDR, T, E> {
+            tmp_ll: Tmp117LL::new(i2c),
+            observer: None,
+            calibration: None,
+            config: None,
+            use_cache: false,
+            shutdown_error: None,
+            session: SessionAccumulator::default(),
+            oneshot_polarity: Polarity::ActiveLow,
+            alert: None,
+        }
+    }
+
+    /// Create a new tmp117 from a i2c bus for a device already configured, e.g. by a bootloader or a previous firmware stage,
+    /// and read back its [settings](Self::read_settings). Nothing is written to the device.
+    /// Note that reading the configuration clears the data ready and alert flags
+    pub fn attach(i2c: T) -> Result<(Self, Settings), Error<E>> {
+        let mut tmp = Self::new(i2c);
+        let settings = tmp.read_settings()?;
+        Ok((tmp, settings))
+    }
+
+    /// Create a new tmp117 from a low level tmp117 driver
+    pub fn new_from_ll(tmp_ll: Tmp117LL<ADDR, T, E>) -> Self {
+        Tmp117::<ADDR, T, E> {
+            tmp_ll,
+            observer: None,
+            calibration: None,
+            config: None,
+            use_cache: false,
+            shutdown_error: None,
+            session: SessionAccumulator::default(),
+            oneshot_polarity: Polarity::ActiveLow,
+            alert: None,
+        }
+    }
+}
+
+impl<const ADDR: u8, T, E, P> Tmp117<ADDR, T, E, P>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: InputPin,
+{
+    /// Create a new tmp117 from a i2c bus and alert pin.
+    /// The waits for the data and the alerts poll the level of the pin instead of the configuration,
+    /// reading the configuration while a conversion completes can clear the data ready flag
+    pub fn new_with_alert(i2c: T, alert: P) -> Self {
+        Self {
+            tmp_ll: Tmp117LL::new(i2c),
+            observer: None,
+            calibration: None,
+            config: None,
+            use_cache: false,
+            shutdown_error: None,
+            session: SessionAccumulator::default(),
+            oneshot_polarity: Polarity::ActiveLow,
+            alert: Some(alert),
+        }
+    }
+}
+
+impl<const ADDR: u8, T, E, P, O, K> Tmp117<ADDR, T, E, P, O, K>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: InputPin,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    /// Destroys the driver and returns the i2c bus, e.g. to hand it to another driver.
+    /// The device is left in its current mode and the alert pin, if any, is dropped
+    pub fn release(self) -> T {
+        self.tmp_ll.release()
+    }
+
+    /// Same as [release](Self::release) but also returns the alert pin, if any
+    pub fn release_with_alert(self) -> (T, Option<P>) {
+        (self.tmp_ll.release(), self.alert)
+    }
+
+    /// Borrow the i2c bus, e.g. for a shared bus
+    pub fn inner(&self) -> &T {
+        self.tmp_ll.inner()
+    }
+
+    /// Mutably borrow the i2c bus, e.g. to probe the bus or do a general call without releasing the driver.
+    /// The caller must not leave the registers of the device in a state the driver doesn't expect,
+    /// the [cached configuration](Self::cached_config) is not updated by writes done through the bus
+    pub fn inner_mut(&mut self) -> &mut T {
+        self.tmp_ll.inner_mut()
+    }
+
+    /// Mutably borrow the low level driver to read, write or edit any typed register, e.g. the [HighLimit] directly.
+    /// Writing the configuration through it desyncs the state kept by the driver: the [cached configuration](Self::cached_config),
+    /// used for the polarity of the alert pin and the [use of the cache](Self::set_use_cached_config)
+    pub fn low_level(&mut self) -> &mut Tmp117LL<ADDR, T, E> {
+        &mut self.tmp_ll
+    }
+
+    /// Set a function called with every temperature successfully read in celsius by
+    /// [oneshot](Tmp117::oneshot), [read_temp](ContinuousHandler::read_temp) and [wait_temp](ContinuousHandler::wait_temp).
+    /// Useful to tee the readings to a logger or telemetry sink without changing the call sites.
+    /// The observer replaces the previous one of the same type, use [with_read_observer](Self::with_read_observer)
+    /// to set a capturing closure
+    pub fn set_read_observer(&mut self, f: O) {
+        self.observer = Some(f);
+    }
+
+    /// Same as [set_read_observer](Self::set_read_observer) but accepts any closure, e.g. one capturing a counter or a channel.
+    /// The type of the observer is part of the type of the driver, so the driver is consumed and returned with the new type
+    pub fn with_read_observer<O2>(self, f: O2) -> Tmp117<ADDR, T, E, P, O2, K>
+    where
+        O2: FnMut(f32),
+    {
+        Tmp117 {
+            tmp_ll: self.tmp_ll,
+            alert: self.alert,
+            observer: Some(f),
+            calibration: self.calibration,
+            config: self.config,
+            use_cache: self.use_cache,
+            shutdown_error: self.shutdown_error,
+            session: self.session,
+            oneshot_polarity: self.oneshot_polarity,
+        }
+    }
+
+    /// Remove the read observer, if any
+    pub fn clear_read_observer(&mut self) {
+        self.observer = None;
+    }
+
+    /// Set a calibration function applied to every temperature read in celsius before it is returned,
+    /// e.g. a 2 or 3 points polynomial correction. It is applied on top of the hardware
+    /// [TemperatureOffset] since the device already adds it to the temperature register.
+    /// The [read observer](Self::set_read_observer) receives the calibrated value.
+    /// Use [with_calibration](Self::with_calibration) to set a capturing closure, e.g. with coefficients loaded at runtime
+    pub fn set_calibration(&mut self, f: K) {
+        self.calibration = Some(f);
+    }
+
+    /// Same as [set_calibration](Self::set_calibration) but accepts any closure.
+    /// The type of the calibration is part of the type of the driver, so the driver is consumed and returned with the new type
+    pub fn with_calibration<K2>(self, f: K2) -> Tmp117<ADDR, T, E, P, O, K2>
+    where
+        K2: FnMut(f32) -> f32,
+    {
+        Tmp117 {
+            tmp_ll: self.tmp_ll,
+            alert: self.alert,
+            observer: self.observer,
+            calibration: Some(f),
+            config: self.config,
+            use_cache: self.use_cache,
+            shutdown_error: self.shutdown_error,
+            session: self.session,
+            oneshot_polarity: self.oneshot_polarity,
+        }
+    }
+
+    /// Remove the calibration function, the temperatures are returned as read
+    pub fn clear_calibration(&mut self) {
+        self.calibration = None;
+    }
+
+    /// Set the polarity of the alert pin used by the [oneshot](Self::oneshot), [ActiveLow](Polarity::ActiveLow) by default.
+    /// The continuous mode uses the [polarity of its config](ContinuousConfig::polarity)
+    pub fn set_oneshot_polarity(&mut self, polarity: Polarity) {
+        self.oneshot_polarity = polarity;
+    }
+
+    /// Returns the last configuration written by the driver, without any bus transaction.
+    /// This reflects what the driver wrote, not necessarily the live state of the device:
+    /// the alert and data ready flags are the ones read before the write and won't be current.
+    /// Returns `None` if the driver didn't write the configuration yet
+    pub fn cached_config(&self) -> Option<Configuration> {
+        self.config
+    }
+
+    /// Trust the [cached configuration](Self::cached_config) instead of reading the device, disabled by default.
+    /// The read-modify-write of every setter and [transaction](Self::config_transaction) starts from the cache,
+    /// saving the read of the configuration, and the mode changes of [set_shutdown](Self::set_shutdown) and the continuous mode
+    /// are skipped entirely when the device is already configured as requested.
+    /// The cache isn't used after a oneshot since the device goes back to shutdown by itself, and it is invalidated by a [reset](Self::reset).
+    /// It is never used with an alert pin, the read of the configuration is what clears a data ready flag left set
+    /// by a previous conversion, without it the pin would stay active and the next wait would return the previous temperature.
+    ///
+    /// Only enable it if nothing else writes the configuration, the cache would not reflect the device anymore
+    pub fn set_use_cached_config(&mut self, use_cache: bool) {
+        self.use_cache = use_cache;
+    }
+
+    /// Returns the cached configuration if it can be used instead of reading the device
+    fn trusted_config(&self) -> Option<Configuration> {
+        // The read of the configuration clears a stale data ready flag keeping the alert pin active
+        self.config.filter(|c| {
+            self.use_cache && self.alert.is_none() && c.mode() != ConversionMode::OneShot
+        })
+    }
+
+    /// Same as [edit_config](Self::edit_config) but does nothing if the [cache is used](Self::set_use_cached_config)
+    /// and the edit doesn't change the cached configuration
+    fn edit_config_skip<F>(&mut self, f: F) -> Result<(), Error<E>>
+    where
+        F: Fn(&mut Configuration),
+    {
+        if let Some(cached) = self.trusted_config() {
+            let mut edited = cached;
+            f(&mut edited);
+            if edited == cached {
+                return Ok(());
+            }
+        }
+        self.edit_config(f)
+    }
+
+    /// Apply several changes to the configuration with a single write, e.g. the mode, average and conversion at once
+    /// instead of a read-modify-write for each setter. The configuration is read once before the changes,
+    /// unless the [cache is used](Self::set_use_cached_config).
+    /// Note that reading the configuration clears the data ready and alert flags
+    pub fn config_transaction<F>(&mut self, f: F) -> Result<(), Error<E>>
+    where
+        F: FnOnce(&mut Configuration),
+    {
+        self.edit_config(f)
+    }
+
+    /// Read the conversion mode of the device. Returns [Error::InvalidMode] if the mode bits hold the reserved value.
+    /// Note that reading the configuration clears the data ready and alert flags
+    pub fn current_mode(&mut self) -> Result<ConversionMode, Error<E>> {
+        let raw = self.tmp_ll.read_raw(RegisterId::Configuration.address())?;
+        let config = decode_config(raw)?;
+        Ok(config.mode())
+    }
+
+    /// Read and decode the configuration, returns [Error::InvalidMode] if the mode bits hold the reserved value
+    /// instead of the [Error::InvalidData] of the low level driver
+    fn read_config(&mut self) -> Result<Configuration, Error<E>> {
+        let raw = self.tmp_ll.read_raw(RegisterId::Configuration.address())?;
+        decode_config(raw)
+    }
+
+    /// Read-modify-write the configuration and cache the written value, the read is skipped if the cache is trusted
+    fn edit_config<F>(&mut self, f: F) -> Result<(), Error<E>>
+    where
+        F: FnOnce(&mut Configuration),
+    {
+        let mut config = match self.trusted_config() {
+            Some(config) => config,
+            None => self.read_config()?,
+        };
+        f(&mut config);
+        self.write_config(config)
+    }
+
+    /// Write the whole configuration and cache it
+    fn write_config(&mut self, config: Configuration) -> Result<(), Error<E>> {
+        self.tmp_ll
+            .write_raw(RegisterId::Configuration.address(), config.into())?;
+        self.config = Some(config);
+        Ok(())
+    }
+
+    /// Same as [edit_config_skip](Self::edit_config_skip), but when `complete` the edit sets every field of the configuration
+    /// except the function of the alert pin, which is kept from the last write of the driver, so it is written directly
+    /// without reading the device first. Falls back to the read-modify-write if the driver didn't write the configuration yet
+    fn edit_config_complete<F>(&mut self, f: F, complete: bool) -> Result<(), Error<E>>
+    where
+        F: Fn(&mut Configuration),
+    {
+        let direct = complete && self.trusted_config().is_none();
+        if let Some(mut config) = self.config.filter(|_| direct) {
+            // The flags are read only, every other writable bit is set by the edit
+            f(&mut config);
+            return self.write_config(config);
+        }
+        self.edit_config_skip(f)
+    }
+
+    /// Read the configuration register once and decode all its fields.
+    /// Returns [Error::InvalidMode] if the conversion mode bits hold the reserved value.
+    ///
+    /// Reading the configuration clears the data ready and alert flags: calling this in a poll loop
+    /// while a [oneshot](Self::oneshot) or a continuous conversion completes can make the driver miss the data ready flag
+    pub fn get_config(&mut self) -> Result<ConfigSnapshot, Error<E>> {
+        let raw = self.tmp_ll.read_raw(RegisterId::Configuration.address())?;
+        let config = decode_config(raw)?;
+        Ok(config.into())
+    }
+
+    /// Read the current settings of the device. Returns [Error::InvalidMode] if the conversion mode bits hold the reserved value.
+    /// Note that reading the configuration clears the data ready and alert flags
+    pub fn read_settings(&mut self) -> Result<Settings, Error<E>> {
+        let raw = self.tmp_ll.read_raw(RegisterId::Configuration.address())?;
+        let config = decode_config(raw)?;
+        let high: HighLimit = self.tmp_ll.read()?;
+        let low: LowLimit = self.tmp_ll.read()?;
+        let offset: TemperatureOffset = self.tmp_ll.read()?;
+        Ok(Settings {
+            mode: config.mode(),
+            average: config.average(),
+            conversion: config.conversion(),
+            polarity: config.polarity(),
+            trigger_mode: config.trigger_mode(),
+            high: counts_to_celsius(u16::from(high)),
+            low: counts_to_celsius(u16::from(low)),
+            offset: counts_to_celsius(u16::from(offset)),
+        })
+    }
+
+    /// Returns the ID of the device
+    pub fn id(&mut self) -> Result<Id, Error<E>> {
+        let id: DeviceID = self.tmp_ll.read()?;
+        Ok(Id {
+            device: id.device_id().into(),
+            revision: id.revision().into(),
+        })
+    }
+
+    /// Check that the device answering at the address is a TMP117, returns its [Id] if it is.
+    /// Returns [Error::WrongDevice] if the device ID isn't 0x117, catching a wrong chip or address at startup
+    pub fn init(&mut self) -> Result<Id, Error<E>> {
+        let id = self.id()?;
+        if !id.is_tmp117() {
+            return Err(Error::WrongDevice { found: id.device });
+        }
+        Ok(id)
+    }
+
+    fn wait_eeprom(&mut self) -> Result<(), Error<E>> {
+        let mut configuration = self.read_config()?;
+        while configuration.eeprom_busy() {
+            configuration = self.read_config()?;
+        }
+
+        Ok(())
+    }
+
+    /// Wait for the device to finish loading the EEPROM in the registers after power-up.
+    /// The `eeprom_busy` flag stays set for about 1.5ms after power-up and configuration written
+    /// during that window can be lost, so call this before configuring the device on fast booting MCUs.
+    /// Polls the [EEPROM] register to avoid clearing the `data_ready` flag of the configuration register.
+    /// Returns [Error::Timeout] if the device is still busy after 10ms
+    pub fn wait_ready_after_power_up<D>(&mut self, delay: &mut D) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.wait_eeprom_timeout(delay, POWER_UP_TIMEOUT_US / 1000)
+    }
+
+    /// Wait for the EEPROM to finish programming, polling every 100us with the delay.
+    /// A write typically completes in 7ms, see [EEPROM_WRITE_TIMEOUT_MS] for a safe bound.
+    /// Polls the [EEPROM] register to avoid clearing the `data_ready` flag of the configuration register.
+    /// Returns [Error::Timeout] if the device is still busy after `max_ms`, e.g. when failing its EEPROM program cycle.
+    /// The EEPROM is always read once, a `max_ms` of 0 only checks that the device is idle
+    pub fn wait_eeprom_timeout<D>(&mut self, delay: &mut D, max_ms: u32) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        let polls = max_ms.saturating_mul(1000) / POLL_INTERVAL_US;
+        let mut waited = 0;
+        loop {
+            // Polled at least once, so an idle device passes even with a `max_ms` of 0
+            let eeprom: EEPROM = self.tmp_ll.read()?;
+            if !eeprom.busy() {
+                return Ok(());
+            }
+            if waited >= polls {
+                return Err(Error::Timeout);
+            }
+            delay.delay_us(POLL_INTERVAL_US);
+            waited += 1;
+        }
+    }
+
+    /// Check if the first conversion completed since power-up or a reset.
+    /// Until then the temperature register holds the power-up value of -256°C,
+    /// so a read right after a reset returns this value instead of a real measurement.
+    /// Note that reading the temperature clears the data ready flag
+    pub fn is_first_conversion_done(&mut self) -> Result<bool, Error<E>> {
+        let temp: Temperature = self.tmp_ll.read()?;
+        Ok(u16::from(temp) != POWER_UP_TEMPERATURE)
+    }
+
+    /// Read the temperature, distinguishing a genuine -256°C from the power-up value of the register.
+    /// Returns [Error::NotYetConverted] if the temperature is the power-up value and the data ready flag is cleared,
+    /// which matters when approaching the bottom of the range.
+    /// Reads the configuration then the temperature, which clears the data ready and alert flags
+    pub fn read_temp_converted(&mut self) -> Result<Celsius, Error<E>> {
+        let config = self.read_config()?;
+        let counts = self.read_temp_raw_i16()?;
+        if counts as u16 == POWER_UP_TEMPERATURE && !config.data_ready() {
+            return Err(Error::NotYetConverted);
+        }
+        Ok(self.celsius_from_counts(counts))
+    }
+
+    /// Wait for the first conversion after power-up or a [general call reset](Self::general_call_reset),
+    /// polling every 100us with the delay, so the first [read_temp](ContinuousHandler::read_temp) is real data.
+    /// Note that [reset](Self::reset) puts the device in shutdown, start a conversion before waiting.
+    /// Returns [Error::Timeout] if no conversion completed after 1s, the longest conversion with 64 averages
+    pub fn wait_first_conversion<D>(&mut self, delay: &mut D) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        for _ in 0..FIRST_CONVERSION_TIMEOUT_US / POLL_INTERVAL_US {
+            if self.is_first_conversion_done()? {
+                return Ok(());
+            }
+            delay.delay_us(POLL_INTERVAL_US);
+        }
+        Err(Error::Timeout)
+    }
+
+    /// Read the temperature register as two's complement counts of [CELCIUS_CONVERSION] without any float conversion,
+    /// useful to log the raw values and convert them offline. The data ready flag is not checked
+    pub fn read_temp_raw_i16(&mut self) -> Result<i16, Error<E>> {
+        let temp: Temperature = self.tmp_ll.read()?;
+
+        // Convert to i16 for two complements
+        let counts = u16::from(temp) as i16;
+        self.session.record(counts);
+        Ok(counts)
+    }
+
+    fn celsius_from_counts(&mut self, counts: i16) -> Celsius {
+        self.calibrated(counts_to_celsius(counts as u16))
+    }
+
+    /// Apply the calibration and call the observer with a temperature in celsius
+    fn calibrated(&mut self, mut val: f32) -> Celsius {
+        if let Some(calibration) = self.calibration.as_mut() {
+            val = calibration(val);
+        }
+        if let Some(observer) = self.observer.as_mut() {
+            observer(val);
+        }
+        Celsius(val)
+    }
+
+    fn read_temp_raw(&mut self) -> Result<Celsius, Error<E>> {
+        let counts = self.read_temp_raw_i16()?;
+        Ok(self.celsius_from_counts(counts))
+    }
+
+    /// Read a register, retrying up to `retries` times on bus errors and waiting the backoff between the attempts, if any
+    fn read_raw_retry<D>(
+        &mut self,
+        addr: u8,
+        retries: u8,
+        mut backoff: Option<(&mut D, u32)>,
+    ) -> Result<u16, Error<E>>
+    where
+        D: DelayNs,
+    {
+        let mut attempt = 0;
+        loop {
+            match self.tmp_ll.read_raw(addr) {
+                Err(ErrorLL::Bus(_)) if attempt < retries => {
+                    attempt += 1;
+                    if let Some((delay, backoff_us)) = &mut backoff {
+                        delay.delay_us(*backoff_us);
+                    }
+                }
+                res => return Ok(res?),
+            }
+        }
+    }
+
+    /// Read the alert flags in any mode, e.g. in thermal mode or after a [oneshot](Self::oneshot), without a continuous handler.
+    /// In alert mode the read clears the flags, in [thermal mode](TriggerMode::Thermal) the high alert flag stays set
+    /// until the temperature falls below the low limit.
+    /// Note that the data ready flag is also cleared by this read
+    pub fn poll_alert(&mut self) -> Result<Alert, Error<E>> {
+        let config = self.read_config()?;
+        Ok(Alert::from(config))
+    }
+
+    /// Read all the registers of [RegisterId::ALL] in a single bus transaction, see [Tmp117LL::snapshot].
+    /// Note that reading the temperature and configuration clears the data ready and alert flags
+    pub fn snapshot(&mut self) -> Result<[u16; RegisterId::ALL.len()], Error<E>> {
+        Ok(self.tmp_ll.snapshot()?)
+    }
+
+    /// Read every register of the device paired with its address, in address order, e.g. to attach a dump to a bug report.
+    /// Uses a single [snapshot](Self::snapshot) transaction.
+    /// Note that reading the temperature and configuration clears the data ready and alert flags
+    pub fn dump_registers(&mut self) -> Result<[(u8, u16); RegisterId::ALL.len()], Error<E>> {
+        let values = self.snapshot()?;
+        Ok(core::array::from_fn(|i| {
+            (RegisterId::ALL[i].address(), values[i])
+        }))
+    }
+
+    /// Read the raw value of any register by address, bypassing the typed registers.
+    /// Returns [Error::InvalidRegister] if the address is outside of the register map (0x00 to 0x0F)
+    pub fn read_raw(&mut self, addr: u8) -> Result<u16, Error<E>> {
+        if addr > RegisterId::DeviceID.address() {
+            return Err(Error::InvalidRegister(addr));
+        }
+        Ok(self.tmp_ll.read_raw(addr)?)
+    }
+
+    /// Write the raw value of any register by address, bypassing the typed registers.
+    /// Returns [Error::InvalidRegister] if the address is outside of the register map (0x00 to 0x0F).
+    ///
+    /// No validation is done on the value, reserved bits and settings are written as is.
+    /// Writing the configuration invalidates the [cached configuration](Self::cached_config)
+    pub fn write_raw(&mut self, addr: u8, value: u16) -> Result<(), Error<E>> {
+        if addr > RegisterId::DeviceID.address() {
+            return Err(Error::InvalidRegister(addr));
+        }
+        self.tmp_ll.write_raw(addr, value)?;
+        if addr == RegisterId::Configuration.address() {
+            self.config = None;
+        }
+        Ok(())
+    }
+
+    /// Check that the device is in a usable state, useful on startup or to recover after a brownout.
+    /// Reads the configuration, which clears the data ready and alert flags, and checks in order that:
+    /// - The soft reset bit is cleared, otherwise the device is in the middle of a reset: [Error::ResetInProgress]
+    /// - The conversion mode is not the reserved `0b10` pattern: [Error::InvalidMode]
+    /// - The EEPROM is not busy longer than the 10ms allowed for the power-up load,
+    ///   which would indicate a stuck EEPROM: [Error::EepromBusy]
+    pub fn health_check<D>(&mut self, delay: &mut D) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        let raw = self.tmp_ll.read_raw(RegisterId::Configuration.address())?;
+        // The mode bits are not meaningful during a reset, check the reset bit first
+        if raw & CONFIG_RESET_BIT != 0 {
+            return Err(Error::ResetInProgress);
+        }
+        decode_config::<E>(raw)?;
+
+        match self.wait_ready_after_power_up(delay) {
+            Err(Error::Timeout) => Err(Error::EepromBusy),
+            res => res,
+        }
+    }
+
+    /// Acknowledge an interrupt of the ALERT pin and return its cause.
+    /// Reads the configuration once, which clears the alert flags, so the next alert can trigger the pin again.
+    /// Note that the data ready flag is also cleared by this read.
+    ///
+    /// This is meant to be called from the bottom half of the interrupt, doing an i2c transaction
+    /// directly in an interrupt handler is usually a bad idea: signal a task from the interrupt and call it from there
+    pub fn handle_alert_interrupt(&mut self) -> Result<Alert, Error<E>> {
+        self.poll_alert()
+    }
+
+    /// Set the high and low limits symmetrically around a center temperature in celsius,
+    /// e.g. `set_window(20.0, 3.0)` alerts if the temperature leaves 20 ± 3 °C.
+    /// The current low limit is read to write the limits in an order that never leaves the high limit below the low limit.
+    /// Returns [Error::InvalidLimits] if the half width is negative and [Error::OutOfRange] if a limit is outside of the registers range
+    pub fn set_window(&mut self, center_c: f32, half_width_c: f32) -> Result<(), Error<E>> {
+        let high = center_c + half_width_c;
+        let low = center_c - half_width_c;
+        if low > high {
+            return Err(Error::InvalidLimits);
+        }
+        let (high, low) = (celsius_to_raw(high)?, celsius_to_raw(low)?);
+
+        // The device must never hold a high limit below the low limit, it could latch a spurious alert.
+        // Moving the window down past the current low limit needs the low limit written first
+        let current_low: LowLimit = self.tmp_ll.read()?;
+        if (high as i16) < (u16::from(current_low) as i16) {
+            self.tmp_ll.write(LowLimit::from(low))?;
+            self.tmp_ll.write(HighLimit::from(high))?;
+        } else {
+            self.tmp_ll.write(HighLimit::from(high))?;
+            self.tmp_ll.write(LowLimit::from(low))?;
+        }
+        Ok(())
+    }
+
+    /// Set the high limit in celsius, returns [Error::OutOfRange] if it is outside of the registers range
+    pub fn set_high_limit(&mut self, high_c: f32) -> Result<(), Error<E>> {
+        self.tmp_ll
+            .write(HighLimit::from(celsius_to_raw(high_c)?))?;
+        Ok(())
+    }
+
+    /// Set the low limit in celsius, returns [Error::OutOfRange] if it is outside of the registers range
+    pub fn set_low_limit(&mut self, low_c: f32) -> Result<(), Error<E>> {
+        self.tmp_ll.write(LowLimit::from(celsius_to_raw(low_c)?))?;
+        Ok(())
+    }
+
+    /// Same as [set_high_limit](Self::set_high_limit) but [clamps](Celsius::clamp_to_range) the limit into the registers range
+    /// instead of returning an error
+    pub fn set_high_limit_clamped(&mut self, high_c: f32) -> Result<(), Error<E>> {
+        let high = Celsius(high_c).clamp_to_range();
+        self.tmp_ll
+            .write(HighLimit::from(celsius_to_counts(high.0)))?;
+        Ok(())
+    }
+
+    /// Same as [set_low_limit](Self::set_low_limit) but [clamps](Celsius::clamp_to_range) the limit into the registers range
+    /// instead of returning an error
+    pub fn set_low_limit_clamped(&mut self, low_c: f32) -> Result<(), Error<E>> {
+        let low = Celsius(low_c).clamp_to_range();
+        self.tmp_ll
+            .write(LowLimit::from(celsius_to_counts(low.0)))?;
+        Ok(())
+    }
+
+    /// Read the high limit in celsius
+    pub fn get_high_limit(&mut self) -> Result<f32, Error<E>> {
+        let high: HighLimit = self.tmp_ll.read()?;
+        Ok(counts_to_celsius(u16::from(high)))
+    }
+
+    /// Read the low limit in celsius
+    pub fn get_low_limit(&mut self) -> Result<f32, Error<E>> {
+        let low: LowLimit = self.tmp_ll.read()?;
+        Ok(counts_to_celsius(u16::from(low)))
+    }
+
+    /// Set the temperature offset in celsius, added to the measured temperature.
+    /// Returns [Error::OutOfRange] if it is outside of the registers range
+    pub fn set_offset(&mut self, offset_c: f32) -> Result<(), Error<E>> {
+        self.tmp_ll
+            .write(TemperatureOffset::from(celsius_to_raw(offset_c)?))?;
+        Ok(())
+    }
+
+    /// Read the temperature offset in celsius
+    pub fn get_offset(&mut self) -> Result<f32, Error<E>> {
+        let offset: TemperatureOffset = self.tmp_ll.read()?;
+        Ok(counts_to_celsius(u16::from(offset)))
+    }
+
+    /// Set the average, the other settings and the conversion mode are kept
+    pub fn set_average(&mut self, average: Average) -> Result<(), Error<E>> {
+        self.edit_config(|r| r.set_average(average))
+    }
+
+    /// Set the conversion cycle, the other settings and the conversion mode are kept
+    pub fn set_conversion(&mut self, conversion: Conversion) -> Result<(), Error<E>> {
+        self.edit_config(|r| r.set_conversion(conversion))
+    }
+
+    /// Read the average currently configured, e.g. loaded from the EEPROM after a reset.
+    /// Note that reading the configuration clears the data ready and alert flags
+    pub fn get_average(&mut self) -> Result<Average, Error<E>> {
+        let config = self.read_config()?;
+        Ok(config.average())
+    }
+
+    /// Read the conversion cycle currently configured, e.g. loaded from the EEPROM after a reset.
+    /// Note that reading the configuration clears the data ready and alert flags
+    pub fn get_conversion(&mut self) -> Result<Conversion, Error<E>> {
+        let config = self.read_config()?;
+        Ok(config.conversion())
+    }
+
+    fn wait_for_data(&mut self) -> Result<(), Error<E>> {
+        self.wait_for_data_in(None)
+    }
+
+    /// Wait for the data, returns [Error::WrongMode] if the device isn't in the expected mode
+    /// Only the configuration is read while waiting since reading the temperature would clear the data ready flag,
+    /// the temperature must be read once after the flag was seen set
+    fn wait_for_data_in(&mut self, mode: Option<ConversionMode>) -> Result<(), Error<E>> {
+        self.select_alert_pin(AlertPinSelect::DataReady)?;
+        let polarity = self.active_polarity();
+        if let Some(p) = &mut self.alert {
+            loop {
+                // Poll the level of the pin, the configuration is only read once it is active
+                while !pin_active(p, polarity)? {}
+
+                // The active pin is the data ready flag, skip the read of the configuration which would clear it.
+                // The temperature read following the wait clears it instead
+                let Some(mode) = mode else {
+                    return Ok(());
+                };
+
+                // The pin is borrowed, read through the low level driver
+                let raw = self.tmp_ll.read_raw(RegisterId::Configuration.address())?;
+                let config = decode_config(raw)?;
+                if mode != config.mode() {
+                    return Err(Error::WrongMode);
+                }
+                if config.data_ready() {
+                    return Ok(());
+                }
+            }
+        }
+
+        // Loop while the data is not ok
+        loop {
+            let config = self.read_config()?;
+            if mode.is_some_and(|m| m != config.mode()) {
+                return Err(Error::WrongMode);
+            }
+            if config.data_ready() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Wait for the data by polling the configuration every interval with the delay,
+    /// the callback is called with the elapsed milliseconds after every interval.
+    /// Returns [Error::Timeout] once `max_ms` elapsed, an interval of 0 is polled every millisecond
+    fn wait_for_data_delay<D, F>(
+        &mut self,
+        delay: &mut D,
+        interval_ms: u32,
+        max_ms: u32,
+        mut on_interval: F,
+    ) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+        F: FnMut(u32),
+    {
+        // A 0 interval would poll without the elapsed time ever reaching the timeout
+        let interval_ms = interval_ms.max(1);
+        let mut elapsed: u32 = 0;
+        loop {
+            let config = self.read_config()?;
+            if config.data_ready() {
+                return Ok(());
+            }
+            if elapsed >= max_ms {
+                return Err(Error::Timeout);
+            }
+            delay.delay_ms(interval_ms);
+            elapsed = elapsed.saturating_add(interval_ms);
+            on_interval(elapsed);
+        }
+    }
+
+    /// Wait for the data by polling the configuration with the delay, returns [Error::Timeout] after `timeout_us`
+    fn wait_for_data_timeout<D>(&mut self, delay: &mut D, timeout_us: u32) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        let mut elapsed = 0;
+        loop {
+            let config = self.read_config()?;
+            if config.data_ready() {
+                return Ok(());
+            }
+            if elapsed >= timeout_us {
+                return Err(Error::Timeout);
+            }
+            delay.delay_us(POLL_INTERVAL_US);
+            elapsed += POLL_INTERVAL_US;
+        }
+    }
+
+    /// Wait for an alert by polling the configuration with the delay, returns [Error::Timeout] after `timeout_us`
+    fn wait_for_alert_timeout<D>(
+        &mut self,
+        delay: &mut D,
+        timeout_us: u32,
+    ) -> Result<Alert, Error<E>>
+    where
+        D: DelayNs,
+    {
+        let mut elapsed = 0;
+        loop {
+            let alert = self.poll_alert()?;
+            if !matches!(alert, Alert::None) {
+                return Ok(alert);
+            }
+            if elapsed >= timeout_us {
+                return Err(Error::Timeout);
+            }
+            delay.delay_us(POLL_INTERVAL_US);
+            elapsed += POLL_INTERVAL_US;
+        }
+    }
+
+    fn wait_for_alert(&mut self) -> Result<Alert, Error<E>> {
+        self.select_alert_pin(AlertPinSelect::Alert)?;
+        let polarity = self.active_polarity();
+        if let Some(p) = &mut self.alert {
+            while !pin_active(p, polarity)? {}
+            return self.poll_alert();
+        }
+
+        loop {
+            let alert = self.poll_alert();
+            if let Ok(Alert::None) = alert {
+                continue;
+            } else {
+                return alert;
+            }
+        }
+    }
+
+    /// Polarity of the alert pin as last written by the driver
+    fn active_polarity(&self) -> Polarity {
+        self.config.map_or(Polarity::ActiveLow, |c| c.polarity())
+    }
+
+    /// Select what the alert pin reflects if the driver has one and it isn't already selected
+    fn select_alert_pin(&mut self, select: AlertPinSelect) -> Result<(), Error<E>> {
+        if self.alert.is_none() || self.config.is_some_and(|c| c.dr_alert() == select) {
+            return Ok(());
+        }
+        self.edit_config(|r| r.set_dr_alert(select))
+    }
+
+    fn set_continuous(
+        &mut self,
+        config: ContinuousConfig,
+    ) -> Result<ContinuousHandler<'_, ADDR, T, E, P, O, K>, Error<E>> {
+        // Validate every value before writing any of them
+        let high = config.high.map(|v| celsius_to_raw(v.0)).transpose()?;
+        let low = config.low.map(|v| celsius_to_raw(v.0)).transpose()?;
+        let offset = config.offset.map(|v| celsius_to_raw(v.0)).transpose()?;
+        if let Some(val) = high {
+            self.tmp_ll.write(HighLimit::from(val))?;
+        }
+        if let Some(val) = low {
+            self.tmp_ll.write(LowLimit::from(val))?;
+        }
+        if let Some(val) = offset {
+            self.tmp_ll.write(TemperatureOffset::from(val))?;
+        }
+
+        let has_pin = self.alert.is_some();
+        self.edit_config_complete(
+            |r| {
+                r.set_mode(ConversionMode::Continuous);
+                if has_pin {
+                    r.set_dr_alert(AlertPinSelect::DataReady);
+                }
+                if let Some(trigger_mode) = config.trigger_mode {
+                    r.set_trigger_mode(trigger_mode);
+                }
+                r.set_polarity(config.polarity);
+                r.set_average(config.average);
+                r.set_conversion(config.conversion);
+            },
+            // With a pin, the read clears a stale data ready flag which would keep the pin active
+            !has_pin && config.trigger_mode.is_some(),
+        )?;
+
+        self.session = SessionAccumulator::default();
+        Ok(ContinuousHandler {
+            tmp117: self,
+            cycle_ms: config.conversion.cycle_time_us(config.average) / 1000,
+            last_sample_ms: None,
+        })
+    }
+
+    fn set_oneshot(
+        &mut self,
+        average: Average,
+        conversion: Option<Conversion>,
+    ) -> Result<(), Error<E>> {
+        let polarity = self.oneshot_polarity;
+        let has_pin = self.alert.is_some();
+        self.edit_config(|r| {
+            r.set_mode(ConversionMode::OneShot);
+            if has_pin {
+                r.set_dr_alert(AlertPinSelect::DataReady);
+            }
+            r.set_polarity(polarity);
+            r.set_average(average);
+            if let Some(conversion) = conversion {
+                r.set_conversion(conversion);
+            }
+        })?;
+        Ok(())
+    }
+
+    /// Put the device in shutdown, stopping any conversion until the next mode change
+    pub fn set_shutdown(&mut self) -> Result<(), Error<E>> {
+        self.edit_config_skip(|r| {
+            r.set_mode(ConversionMode::Shutdown);
+        })?;
+        Ok(())
+    }
+
+    /// Resets the device and put it in shutdown, waiting the typical 2ms of the reset
+    pub fn reset<D>(&mut self, delay: &mut D) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.reset_with_delay(delay, RESET_DELAY_MS)
+    }
+
+    /// Same as [reset](Self::reset) but waits `ms` for the reset to complete,
+    /// for marginal parts that are not quite ready after the typical 2ms
+    pub fn reset_with_delay<D>(&mut self, delay: &mut D, ms: u32) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.edit_config(|r| {
+            r.set_reset(true);
+        })?;
+        delay.delay_ms(ms);
+        self.config = None;
+        self.set_shutdown()?;
+        Ok(())
+    }
+
+    /// Reload the configuration, limits and offset stored in the EEPROM, leaving the device in the stored mode
+    /// unlike [reset](Self::reset) which forces the shutdown. Only this device is affected, unlike the
+    /// [general call reset](Self::general_call_reset). The sequence is:
+    /// 1. Set the soft reset bit of the configuration, the device reloads the EEPROM like on power-up
+    /// 2. Wait the 2ms of the reset
+    /// 3. Poll the [EEPROM] register until the busy flag clears, see [wait_ready_after_power_up](Self::wait_ready_after_power_up)
+    ///
+    /// Returns [Error::Timeout] if the EEPROM is still busy 10ms after the reset
+    pub fn reload_eeprom<D>(&mut self, delay: &mut D) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.edit_config(|r| {
+            r.set_reset(true);
+        })?;
+        self.config = None;
+        delay.delay_ms(RESET_DELAY_MS);
+        self.wait_ready_after_power_up(delay)
+    }
+
+    /// Resets every device on the bus with the I2C general call and waits the 2ms of the reset.
+    /// This affects all the devices supporting the general call on the bus, not only the TMP117s, which is handy at boot
+    /// to put every sensor in a known state at once. The devices reload their configuration from the EEPROM, see [reset](Self::reset)
+    /// to reset only this device
+    pub fn general_call_reset<D>(&mut self, delay: &mut D) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.tmp_ll.general_call_reset()?;
+        self.config = None;
+        delay.delay_ms(RESET_DELAY_MS);
+        Ok(())
+    }
+
+    /// Write data to user eeprom. Note that this is blocking because we wait for write on the eeprom to complete.
+    /// The first value is ignored since [UEEPROM1] holds the [NIST traceability ID](Self::read_nist_id),
+    /// use [write_eeprom_including_nist](Self::write_eeprom_including_nist) to overwrite it
+    pub fn write_eeprom(&mut self, values: [u16; 3]) -> Result<(), Error<E>> {
+        self.wait_eeprom()?;
+        self.tmp_ll.write(UEEPROM2::from(values[1]))?;
+
+        self.wait_eeprom()?;
+        self.tmp_ll.write(UEEPROM3::from(values[2]))?;
+
+        Ok(())
+    }
+
+    /// Same as [write_eeprom](Self::write_eeprom) but waits at most [EEPROM_WRITE_TIMEOUT_MS] for each write to complete
+    /// instead of spinning on the busy flag, returns [Error::Timeout] if the EEPROM stays busy
+    pub fn write_eeprom_timeout<D>(
+        &mut self,
+        values: [u16; 3],
+        delay: &mut D,
+    ) -> Result<(), Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.wait_eeprom_timeout(delay, EEPROM_WRITE_TIMEOUT_MS)?;
+        self.tmp_ll.write(UEEPROM2::from(values[1]))?;
+
+        self.wait_eeprom_timeout(delay, EEPROM_WRITE_TIMEOUT_MS)?;
+        self.tmp_ll.write(UEEPROM3::from(values[2]))?;
+
+        Ok(())
+    }
+
+    /// Same as [write_eeprom](Self::write_eeprom) but also writes the first value to [UEEPROM1],
+    /// destroying the NIST traceability ID
+    pub fn write_eeprom_including_nist(&mut self, values: [u16; 3]) -> Result<(), Error<E>> {
+        self.wait_eeprom()?;
+        self.tmp_ll.write(UEEPROM1::from(values[0]))?;
+
+        self.wait_eeprom()?;
+        self.tmp_ll.write(UEEPROM2::from(values[1]))?;
+
+        self.wait_eeprom()?;
+        self.tmp_ll.write(UEEPROM3::from(values[2]))?;
+
+        Ok(())
+    }
+
+    fn set_eeprom_unlock(&mut self, unlock: bool) -> Result<(), Error<E>> {
+        self.tmp_ll.edit(|r: &mut EEPROM| {
+            r.set_unlock(unlock);
+        })?;
+        Ok(())
+    }
+
+    /// Unlock the EEPROM, waiting for any pending programming to complete first.
+    /// While unlocked, every write to the configuration, limits, offset or user EEPROM registers also programs
+    /// the EEPROM so the value survives a power cycle. Each programming takes about 7ms during which the EEPROM is busy,
+    /// so the writes are slow: [lock](Self::lock_eeprom) it as soon as the values are written
+    pub fn unlock_eeprom(&mut self) -> Result<(), Error<E>> {
+        self.wait_eeprom()?;
+        self.set_eeprom_unlock(true)
+    }
+
+    /// Lock the EEPROM once the pending programming completes, the writes to the registers are volatile again
+    pub fn lock_eeprom(&mut self) -> Result<(), Error<E>> {
+        self.wait_eeprom()?;
+        self.set_eeprom_unlock(false)
+    }
+
+    /// Program the EEPROM so the device boots with the given settings.
+    /// Unlocks the EEPROM, writes the configuration which programs the EEPROM backed bits,
+    /// waits for the programming to complete and locks the EEPROM back.
+    ///
+    /// The mode, conversion cycle, average, trigger mode, polarity and alert pin select bits are EEPROM backed,
+    /// the current mode is also stored so the device boots in it.
+    /// The soft reset bit and the status flags (alerts, data ready and EEPROM busy) are volatile and never persisted
+    pub fn persist_config(
+        &mut self,
+        average: Average,
+        conversion: Conversion,
+        polarity: Polarity,
+        trigger_mode: TriggerMode,
+    ) -> Result<(), Error<E>> {
+        self.unlock_eeprom()?;
+        self.edit_config(|r| {
+            r.set_average(average);
+            r.set_conversion(conversion);
+            r.set_polarity(polarity);
+            r.set_trigger_mode(trigger_mode);
+        })?;
+        self.lock_eeprom()
+    }
+
+    /// Read the NIST traceability ID stored in [UEEPROM1]
+    pub fn read_nist_id(&mut self) -> Result<u16, Error<E>> {
+        let u1: UEEPROM1 = self.tmp_ll.read()?;
+        Ok(u1.into())
+    }
+
+    /// Read a single user EEPROM register, index 0 to 2 maps to [UEEPROM1] to [UEEPROM3].
+    /// Returns [Error::InvalidIndex] for any other index
+    pub fn read_user_eeprom(&mut self, index: u8) -> Result<u16, Error<E>> {
+        let addr = user_eeprom_address(index)?;
+        Ok(self.tmp_ll.read_raw(addr)?)
+    }
+
+    /// Write a single user EEPROM register without touching the others, index 1 and 2 maps to [UEEPROM2] and [UEEPROM3].
+    /// Returns [Error::InvalidIndex] for any other index, including 0 since [UEEPROM1] holds the [NIST traceability ID](Self::read_nist_id),
+    /// use [write_user_eeprom_including_nist](Self::write_user_eeprom_including_nist) to overwrite it.
+    /// Note that this is blocking because we wait for the write on the eeprom to complete
+    pub fn write_user_eeprom(&mut self, index: u8, value: u16) -> Result<(), Error<E>> {
+        if index == 0 {
+            return Err(Error::InvalidIndex(index));
+        }
+        self.write_user_eeprom_including_nist(index, value)
+    }
+
+    /// Same as [write_user_eeprom](Self::write_user_eeprom) but also accepts the index 0, destroying the NIST traceability ID
+    pub fn write_user_eeprom_including_nist(
+        &mut self,
+        index: u8,
+        value: u16,
+    ) -> Result<(), Error<E>> {
+        let addr = user_eeprom_address(index)?;
+        self.wait_eeprom()?;
+        self.tmp_ll.write_raw(addr, value)?;
+        self.wait_eeprom()
+    }
+
+    /// Program the current configuration, limits and offset in the EEPROM so the device boots with them.
+    /// Unlocks the EEPROM, writes back every register as read from the device waiting for the programming
+    /// to complete after each write, and locks the EEPROM back. Takes about 30ms.
+    /// Note that reading the configuration clears the data ready and alert flags
+    pub fn save_config_to_eeprom(&mut self) -> Result<(), Error<E>> {
+        self.unlock_eeprom()?;
+
+        // Writing the configuration back as read programs it
+        self.edit_config(|_| {})?;
+        self.wait_eeprom()?;
+
+        let high: HighLimit = self.tmp_ll.read()?;
+        self.tmp_ll.write(high)?;
+        self.wait_eeprom()?;
+
+        let low: LowLimit = self.tmp_ll.read()?;
+        self.tmp_ll.write(low)?;
+        self.wait_eeprom()?;
+
+        let offset: TemperatureOffset = self.tmp_ll.read()?;
+        self.tmp_ll.write(offset)?;
+
+        self.lock_eeprom()
+    }
+
+    /// Read the data from the eeprom
+    pub fn read_eeprom(&mut self) -> Result<[u16; 3], Error<E>> {
+        let u1: UEEPROM1 = self.tmp_ll.read()?;
+        let u2: UEEPROM2 = self.tmp_ll.read()?;
+        let u3: UEEPROM3 = self.tmp_ll.read()?;
+
+        Ok([u1.into(), u2.into(), u3.into()])
+    }
+
+    /// Wait for data and read the temperature in celsius and shutdown since it's a oneshot
+    pub fn oneshot(&mut self, average: Average) -> Result<Celsius, Error<E>> {
+        self.set_oneshot(average, None)?;
+        self.wait_for_data()?;
+        let data = self.read_temp_raw()?;
+        Ok(data)
+    }
+
+    /// Same as [oneshot](Self::oneshot) but also sets the conversion cycle.
+    /// The duration of a oneshot only depends on the average, the conversion cycle is the one kept
+    /// for a following [continuous](Self::continuous) mode, so it isn't inherited from a previous session
+    pub fn oneshot_with(
+        &mut self,
+        average: Average,
+        conversion: Conversion,
+    ) -> Result<Celsius, Error<E>> {
+        self.set_oneshot(average, Some(conversion))?;
+        self.wait_for_data()?;
+        self.read_temp_raw()
+    }
+
+    /// Same as [oneshot](Self::oneshot) but returns the temperature as any [FromRawTemperature] type.
+    /// The calibration and read observer are not applied since they work on celsius floats
+    pub fn oneshot_as<U: FromRawTemperature>(&mut self, average: Average) -> Result<U, Error<E>> {
+        self.set_oneshot(average, None)?;
+        self.wait_for_data()?;
+        let counts = self.read_temp_raw_i16()?;
+        Ok(U::from_raw(counts))
+    }
+
+    /// Same as [oneshot](Self::oneshot) but returns the temperature in fahrenheit
+    pub fn oneshot_fahrenheit(&mut self, average: Average) -> Result<f32, Error<E>> {
+        Ok(celsius_to_fahrenheit(self.oneshot(average)?.0))
+    }
+
+    /// Same as [oneshot](Self::oneshot) but returns the temperature in kelvin
+    pub fn oneshot_kelvin(&mut self, average: Average) -> Result<f32, Error<E>> {
+        Ok(celsius_to_kelvin(self.oneshot(average)?.0))
+    }
+
+    /// Do a [oneshot](Self::oneshot) without busy polling the data ready flag, which can be cleared by a read of the
+    /// configuration happening while the conversion completes, see the [TI forum](https://e2e.ti.com/support/sensors-group/sensors/f/sensors-forum/1019457/tmp117-data_ready-flag-cleared-incorrectly-if-data-becomes-ready-during-read-of-configuration-register).
+    /// Sleeps for the expected conversion time of the average before reading the configuration, then polls it with the delay
+    /// and returns [Error::Timeout] if the data is still not ready `timeout_ms` after the start of the conversion
+    pub fn oneshot_with_timeout<D>(
+        &mut self,
+        average: Average,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<Celsius, Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.set_oneshot(average, None)?;
+        let conversion_us = average.conversion_time_us();
+        delay.delay_us(conversion_us);
+        self.wait_for_data_timeout(
+            delay,
+            timeout_ms
+                .saturating_mul(1000)
+                .saturating_sub(conversion_us),
+        )?;
+        self.read_temp_raw()
+    }
+
+    /// Do a [oneshot](Self::oneshot) sleeping once for the [conversion time](Average::conversion_time_us) of the average
+    /// instead of polling the data ready flag, then read the temperature without reading the configuration.
+    /// The duration of a oneshot only depends on the average, the conversion cycle doesn't apply
+    pub fn oneshot_with_delay<D>(
+        &mut self,
+        average: Average,
+        delay: &mut D,
+    ) -> Result<Celsius, Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.set_oneshot(average, None)?;
+        delay.delay_us(average.conversion_time_us());
+        self.read_temp_raw()
+    }
+
+    /// Do a [oneshot](Self::oneshot) and feed the temperature to the filter, returns the filtered temperature in celsius
+    pub fn read_filtered(
+        &mut self,
+        average: Average,
+        filter: &mut OneShotFilter,
+    ) -> Result<f32, Error<E>> {
+        let val = self.oneshot(average)?;
+        Ok(filter.update(val.0))
+    }
+
+    /// Set the device to continuous and return the handler, without a closure.
+    /// The handler borrows the driver until it is [stopped](ContinuousHandler::stop) or dropped, both put the device back in shutdown.
+    /// Useful to keep the handler across the iterations of a state machine
+    pub fn start_continuous(
+        &mut self,
+        config: ContinuousConfig,
+    ) -> Result<ContinuousHandler<'_, ADDR, T, E, P, O, K>, Error<E>> {
+        self.set_continuous(config)
+    }
+
+    /// Take the error of the shutdown done when a [ContinuousHandler] was dropped, e.g. one of [start_continuous](Self::start_continuous).
+    /// The drop can't return it so it is kept until taken, even across other sessions.
+    /// [continuous](Self::continuous) and [thermal](Self::thermal) already return the one of their own session
+    pub fn take_shutdown_error(&mut self) -> Option<Error<E>> {
+        self.shutdown_error.take()
+    }
+
+    /// Run the closure in continuous mode and return the error of its shutdown, if any, after the one of the closure.
+    /// An error left by a previously dropped handler stays for [take_shutdown_error](Self::take_shutdown_error)
+    fn run_continuous<F>(&mut self, config: ContinuousConfig, f: F) -> Result<(), Error<E>>
+    where
+        F: FnOnce(ContinuousHandler<'_, ADDR, T, E, P, O, K>) -> Result<(), Error<E>>,
+    {
+        let pending = self.shutdown_error.take();
+        let res = self.set_continuous(config).and_then(f);
+        let shutdown = core::mem::replace(&mut self.shutdown_error, pending);
+        res?;
+        shutdown.map_or(Ok(()), Err)
+    }
+
+    /// Pass a config and closure for the continuous mode.
+    /// The device gets set to continuous, then the function is called with the handler
+    /// and finally the device is shutdown when the handler is dropped, even if the closure panics.
+    /// The error of the shutdown, if any, is returned after the one of the closure
+    ///
+    /// The handler mutably borrows the driver, so no other mode operation such as a [oneshot](Self::oneshot)
+    /// can be interleaved with the continuous reads, the compiler rejects it rather than the driver at runtime
+    ///
+    /// Entering the continuous mode takes one write per limit and offset set in the config, then a read and a write
+    /// of the configuration. The read is skipped when the [cached configuration is used](Self::set_use_cached_config),
+    /// or without an alert pin when the [trigger mode](ContinuousConfig::trigger_mode) is set and the driver already
+    /// wrote the configuration, since every field is then known: the configuration takes a single write instead of 2 transactions.
+    /// With an alert pin the read is always done, it clears a data ready flag left set by a previous conversion
+    /// which would keep the pin active and make the first wait return the previous temperature
+    pub fn continuous<F>(&mut self, config: ContinuousConfig, f: F) -> Result<(), Error<E>>
+    where
+        F: FnOnce(ContinuousHandler<'_, ADDR, T, E, P, O, K>) -> Result<(), Error<E>>,
+    {
+        self.run_continuous(config, f)
+    }
+
+    /// Pass a config and closure for the thermal mode, the device converts continuously like in [continuous](Self::continuous)
+    /// with the [trigger mode](ContinuousConfig::trigger_mode) forced to thermal so the alert acts as a thermostat:
+    /// the high limit is the trip point and the low limit the hysteresis release point.
+    /// The high alert is set when the temperature goes above the high limit and stays set until it drops below the low limit,
+    /// reading the configuration doesn't clear it. The low alert is always 0 in this mode.
+    /// Returns [Error::InvalidLimits] if both limits are given and the low limit is above the high limit
+    pub fn thermal<F>(&mut self, mut config: ContinuousConfig, f: F) -> Result<(), Error<E>>
+    where
+        F: FnOnce(ContinuousHandler<'_, ADDR, T, E, P, O, K>) -> Result<(), Error<E>>,
+    {
+        if let (Some(high), Some(low)) = (config.high, config.low) {
+            if low > high {
+                return Err(Error::InvalidLimits);
+            }
+        }
+        config.trigger_mode = Some(TriggerMode::Thermal);
+        self.run_continuous(config, f)
+    }
+
+    /// Same as [continuous](Self::continuous) but returns the [stats](SessionStats) of the temperatures read during the session.
+    /// The stats are computed from the register values, without the calibration
+    pub fn continuous_with_stats<F>(
+        &mut self,
+        config: ContinuousConfig,
+        f: F,
+    ) -> Result<SessionStats, Error<E>>
+    where
+        F: FnOnce(ContinuousHandler<'_, ADDR, T, E, P, O, K>) -> Result<(), Error<E>>,
+    {
+        self.continuous(config, f)?;
+        Ok(self.session.stats())
+    }
+}
+
+impl<const ADDR: u8, T, E, P, O, K> Tmp117<ADDR, T, E, P, O, K>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: InputPin,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    /// Returns a guard giving access to the driver that puts the device in shutdown when it goes out of scope,
+    /// so a device left in continuous mode, e.g. by an early return, stops converting and drawing current
+    pub fn with_auto_shutdown(&mut self) -> AutoShutdown<'_, ADDR, T, E, P, O, K> {
+        AutoShutdown { tmp117: self }
+    }
+}
+
+/// Returns if the alert pin is at its active level
+fn pin_active<P: InputPin, E>(pin: &mut P, polarity: Polarity) -> Result<bool, Error<E>> {
+    match polarity {
+        Polarity::ActiveLow => pin.is_low(),
+        Polarity::ActiveHigh => pin.is_high(),
+    }
+    .map_err(|_| Error::AlertPin)
+}
+
+/// Guard putting the device in shutdown when dropped, see [with_auto_shutdown](Tmp117::with_auto_shutdown).
+/// The shutdown is best effort since drop can't fail, the error is ignored.
+/// Call [set_shutdown](Tmp117::set_shutdown) before leaving the scope to get it
+pub struct AutoShutdown<'a, const ADDR: u8, T, E, P = DummyPin, O = fn(f32), K = fn(f32) -> f32>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: InputPin,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    tmp117: &'a mut Tmp117<ADDR, T, E, P, O, K>,
+}
+
+impl<'a, const ADDR: u8, T, E, P, O, K> Deref for AutoShutdown<'a, ADDR, T, E, P, O, K>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: InputPin,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    type Target = Tmp117<ADDR, T, E, P, O, K>;
+
+    fn deref(&self) -> &Self::Target {
+        self.tmp117
+    }
+}
+
+impl<'a, const ADDR: u8, T, E, P, O, K> DerefMut for AutoShutdown<'a, ADDR, T, E, P, O, K>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: InputPin,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.tmp117
+    }
+}
+
+impl<'a, const ADDR: u8, T, E, P, O, K> Drop for AutoShutdown<'a, ADDR, T, E, P, O, K>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: InputPin,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    fn drop(&mut self) {
+        let _ = self.tmp117.set_shutdown();
+    }
+}
+
+/// Handler for the continuous mode.
+/// The device is put back in shutdown when the handler is dropped, making sure it
+/// returns to low power however the handler goes out of scope.
+/// The error of that shutdown is kept for [take_shutdown_error](Tmp117::take_shutdown_error),
+/// use [stop](Self::stop) to get it directly
+///
+/// Only one handler can exist at a time and the driver can't be used while it is alive,
+/// which keeps the mode of the device consistent with the operations done on it
+pub struct ContinuousHandler<
+    'a,
+    const ADDR: u8,
+    T,
+    E,
+    P = DummyPin,
+    O = fn(f32),
+    K = fn(f32) -> f32,
+> where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: InputPin,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    tmp117: &'a mut Tmp117<ADDR, T, E, P, O, K>,
+    cycle_ms: u32,
+    last_sample_ms: Option<u32>,
+}
+
+impl<'a, const ADDR: u8, T, E, P, O, K> Drop for ContinuousHandler<'a, ADDR, T, E, P, O, K>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: InputPin,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    fn drop(&mut self) {
+        // Drop can't fail, keep the error so it can be returned by the driver
+        if let Err(e) = self.tmp117.set_shutdown() {
+            #[cfg(feature = "defmt")]
+            defmt::warn!(
+                "tmp117: shutdown of the dropped continuous handler failed: {}",
+                e.erase()
+            );
+            self.tmp117.shutdown_error = Some(e);
+        }
+    }
+}
+
+impl<'a, const ADDR: u8, T, E, P, O, K> ContinuousHandler<'a, ADDR, T, E, P, O, K>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: InputPin,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    /// Put the device back in shutdown and end the session, unlike dropping the handler the error of the shutdown is returned
+    pub fn stop(self) -> Result<(), Error<E>> {
+        let res = self.tmp117.set_shutdown();
+        // Already shutdown, skip the one of the drop
+        core::mem::forget(self);
+        res
+    }
+
+    /// Read the temperature in celsius, return an error if the value of the temperature is not ready.
+    ///
+    /// The configuration is read before the temperature: reading either register clears the data ready flag,
+    /// so reading the temperature first would always report the data as not ready. Reading the temperature right
+    /// after the flag was seen set returns the conversion that set it, a conversion completing in between only
+    /// updates the temperature to a newer value
+    pub fn read_temp(&mut self) -> Result<Celsius, Error<E>> {
+        let config = self.tmp117.read_config()?;
+        if !config.data_ready() {
+            return Err(Error::DataNotReady);
+        }
+
+        let val = self.tmp117.read_temp_raw()?;
+        Ok(val)
+    }
+
+    /// Same as [read_temp](Self::read_temp) but retries every register read up to `retries` times on [bus errors](Error::Bus),
+    /// e.g. a NACK on a noisy bus. The other errors, such as [Error::DataNotReady], are returned right away.
+    /// Each register is retried on its own so a failed temperature read doesn't read the configuration again, which would clear the data ready flag
+    pub fn read_temp_retry(&mut self, retries: u8) -> Result<Celsius, Error<E>> {
+        self.read_temp_retry_in(retries, None::<(&mut NoDelay, u32)>)
+    }
+
+    /// Same as [read_temp_retry](Self::read_temp_retry) but waits `backoff_us` with the delay before every retry
+    pub fn read_temp_retry_with_backoff<D>(
+        &mut self,
+        retries: u8,
+        delay: &mut D,
+        backoff_us: u32,
+    ) -> Result<Celsius, Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.read_temp_retry_in(retries, Some((delay, backoff_us)))
+    }
+
+    fn read_temp_retry_in<D>(
+        &mut self,
+        retries: u8,
+        mut backoff: Option<(&mut D, u32)>,
+    ) -> Result<Celsius, Error<E>>
+    where
+        D: DelayNs,
+    {
+        let backoff_ref = backoff.as_mut().map(|(d, us)| (&mut **d, *us));
+        let config = self.tmp117.read_raw_retry(
+            RegisterId::Configuration.address(),
+            retries,
+            backoff_ref,
+        )?;
+        let config = decode_config(config)?;
+        if !config.data_ready() {
+            return Err(Error::DataNotReady);
+        }
+
+        let counts =
+            self.tmp117
+                .read_raw_retry(RegisterId::Temperature.address(), retries, backoff)?
+                as i16;
+        self.tmp117.session.record(counts);
+        Ok(self.tmp117.celsius_from_counts(counts))
+    }
+
+    /// Read the temperature in hundredths of a degree fahrenheit, return an error if the value of the temperature is not ready.
+    /// Uses [fahrenheit_centi_from_counts] so no float is involved, the calibration and read observer are not applied
+    pub fn read_temp_fahrenheit_centi(&mut self) -> Result<i32, Error<E>> {
+        let config = self.tmp117.read_config()?;
+        if !config.data_ready() {
+            return Err(Error::DataNotReady);
+        }
+
+        let counts = self.tmp117.read_temp_raw_i16()?;
+        Ok(fahrenheit_centi_from_counts(counts))
+    }
+
+    /// Read the configuration once then the temperature, returning both with the alert and data ready flags.
+    /// Reading the configuration clears the flags, so this avoids losing them between a [read_temp](Self::read_temp)
+    /// and a [get_alert](Self::get_alert). The temperature is returned even if the data is not ready
+    pub fn read_measurement(&mut self) -> Result<Measurement, Error<E>> {
+        let config = self.tmp117.read_config()?;
+        let celsius = self.tmp117.read_temp_raw()?;
+        Ok(Measurement {
+            celsius,
+            alert: Alert::from(config),
+            data_ready: config.data_ready(),
+        })
+    }
+
+    /// Same as [read_measurement](Self::read_measurement) but returns a compact [MeasurementFrame] with the raw temperature,
+    /// the calibration and read observer are not applied
+    pub fn read_frame(&mut self) -> Result<MeasurementFrame, Error<E>> {
+        let config = self.tmp117.read_config()?;
+        let counts = self.tmp117.read_temp_raw_i16()?;
+        Ok(MeasurementFrame::new(counts, config))
+    }
+
+    /// Read the temperature as any [FromRawTemperature] type, return an error if the value of the temperature is not ready.
+    /// The calibration and read observer are not applied since they work on celsius floats
+    pub fn read_temp_as<U: FromRawTemperature>(&mut self) -> Result<U, Error<E>> {
+        let config = self.tmp117.read_config()?;
+        if !config.data_ready() {
+            return Err(Error::DataNotReady);
+        }
+
+        let counts = self.tmp117.read_temp_raw_i16()?;
+        Ok(U::from_raw(counts))
+    }
+
+    /// Read the temperature in celsius with its saturation status, return an error if the value of the temperature is not ready.
+    /// A sensor pinned at the limits of the register usually indicates a wiring fault or an extreme out of range condition.
+    /// Since the data ready flag must be set, the –256 °C reset value is never reported as a saturation.
+    ///
+    /// Also verifies that the device is still in continuous mode and returns [Error::WrongMode] otherwise,
+    /// catching another task or a glitch having changed the mode. The mode is decoded from the configuration
+    /// read done to check the data ready flag, so it costs the same two transactions as [read_temp](Self::read_temp)
+    pub fn read_temp_checked(&mut self) -> Result<(Celsius, TempStatus), Error<E>> {
+        let config = self.tmp117.read_config()?;
+        if config.mode() != ConversionMode::Continuous {
+            return Err(Error::WrongMode);
+        }
+        if !config.data_ready() {
+            return Err(Error::DataNotReady);
+        }
+
+        let counts = self.tmp117.read_temp_raw_i16()?;
+        Ok((
+            self.tmp117.celsius_from_counts(counts),
+            TempStatus::from(counts),
+        ))
+    }
+
+    /// Wait for the data to be ready and read the temperature in celsius.
+    /// With an alert pin, the temperature is read once the pin is active without reading the configuration,
+    /// saving a transaction per sample and avoiding the data ready flag being cleared by the poll of the configuration
+    pub fn wait_temp(&mut self) -> Result<Celsius, Error<E>> {
+        self.tmp117.wait_for_data()?;
+        let val = self.tmp117.read_temp_raw()?;
+        Ok(val)
+    }
+
+    /// Wait for `n` successive conversions and return their average in celsius, a software moving window complementing
+    /// the hardware [Average] without its long cycle times. The counts are summed and scaled once, the calibration and
+    /// read observer are applied to the average. At least one conversion is read
+    pub fn read_averaged(&mut self, n: usize) -> Result<Celsius, Error<E>> {
+        let n = n.max(1);
+        let mut sum = 0i64;
+        for _ in 0..n {
+            self.tmp117.wait_for_data()?;
+            sum += self.tmp117.read_temp_raw_i16()? as i64;
+        }
+        let mean = scale_counts(sum as f32 / n as f32);
+        Ok(self.tmp117.calibrated(mean))
+    }
+
+    /// Wait for the data to be ready and read the temperature in celsius, calling `on_progress` with the elapsed
+    /// milliseconds every interval while waiting. Useful to give feedback with long cycles, up to 16s with [Conversion::Ms16000].
+    /// The configuration is polled every interval with the delay instead of continuously, an interval of 0 polls every millisecond.
+    /// Returns [Error::Timeout] if the data is still not ready after two conversion cycles
+    pub fn wait_temp_with_progress<D, F>(
+        &mut self,
+        delay: &mut D,
+        interval_ms: u32,
+        on_progress: F,
+    ) -> Result<Celsius, Error<E>>
+    where
+        D: DelayNs,
+        F: FnMut(u32),
+    {
+        self.tmp117.wait_for_data_delay(
+            delay,
+            interval_ms,
+            self.cycle_ms.saturating_mul(DATA_TIMEOUT_CYCLES),
+            on_progress,
+        )?;
+        let val = self.tmp117.read_temp_raw()?;
+        Ok(val)
+    }
+
+    /// Wait for the data to be ready and read the temperature in celsius.
+    /// Returns [Error::WrongMode] if the device is not in continuous mode anymore instead of waiting forever.
+    /// The mode is checked on every poll of the configuration, without additional transactions
+    pub fn wait_temp_checked(&mut self) -> Result<Celsius, Error<E>> {
+        self.tmp117
+            .wait_for_data_in(Some(ConversionMode::Continuous))?;
+        let val = self.tmp117.read_temp_raw()?;
+        Ok(val)
+    }
+
+    /// Collect successive readings in the buffer and compute their noise statistics in raw counts.
+    /// Useful to pick an averaging empirically, a higher [Average] should reduce the variance.
+    /// Collects `samples` readings, limited to the length of the buffer. The configuration is polled every
+    /// millisecond with the delay while waiting for a reading, returns [Error::Timeout] if a reading takes more than two
+    /// conversion cycles. The readings don't go through the calibration and observer
+    pub fn measure_noise<D>(
+        &mut self,
+        samples: usize,
+        buf: &mut [i16],
+        delay: &mut D,
+    ) -> Result<NoiseStats, Error<E>>
+    where
+        D: DelayNs,
+    {
+        let samples = samples.min(buf.len());
+        let buf = &mut buf[..samples];
+        for sample in buf.iter_mut() {
+            self.tmp117.wait_for_data_delay(
+                delay,
+                1,
+                self.cycle_ms.saturating_mul(DATA_TIMEOUT_CYCLES),
+                |_| {},
+            )?;
+            *sample = self.tmp117.read_temp_raw_i16()?;
+        }
+        Ok(NoiseStats::from_counts(buf))
+    }
+
+    /// Wait for the data to be ready and read the temperature in celsius, synchronized on the conversion cycle.
+    /// Also returns if samples were missed: more than one conversion cycle elapsed since the previous call according to the clock,
+    /// so the data ready flag was set more than once. The first call never reports an overrun.
+    /// Useful for signal processing where the samples must be uniform
+    pub fn read_synced<C>(&mut self, clock: &mut C) -> Result<(Celsius, bool), Error<E>>
+    where
+        C: Clock,
+    {
+        self.tmp117.wait_for_data()?;
+        let now = clock.now_ms();
+        let overrun = self.overrun(now);
+        let val = self.tmp117.read_temp_raw()?;
+        Ok((val, overrun))
+    }
+
+    /// Read the temperature in celsius, without waiting, and estimate the milliseconds until the next conversion is ready.
+    /// The estimation uses the cycle time and the last time the data ready flag was seen set by this call or
+    /// [read_synced](Self::read_synced), the first call may return the full cycle time.
+    /// Useful to sleep precisely until the next sample
+    pub fn read_temp_and_next_in<C>(&mut self, clock: &mut C) -> Result<(Celsius, u32), Error<E>>
+    where
+        C: Clock,
+    {
+        let config = self.tmp117.read_config()?;
+        let now = clock.now_ms();
+        if config.data_ready() {
+            self.last_sample_ms = Some(now);
+        }
+        let val = self.tmp117.read_temp_raw()?;
+
+        let next_in = match self.last_sample_ms {
+            Some(last) => self.cycle_ms - now.wrapping_sub(last) % self.cycle_ms,
+            None => self.cycle_ms,
+        };
+        Ok((val, next_in))
+    }
+
+    fn overrun(&mut self, now: u32) -> bool {
+        // Allow half a cycle of jitter before considering a sample was missed
+        let overrun = self
+            .last_sample_ms
+            .is_some_and(|last| now.wrapping_sub(last) > self.cycle_ms + self.cycle_ms / 2);
+        self.last_sample_ms = Some(now);
+        overrun
+    }
+
+    /// Set the high limit in celsius while the conversions continue.
+    /// The limit is compared to the next conversion result, an alert already raised stays set until read
+    pub fn set_high_limit(&mut self, high_c: f32) -> Result<(), Error<E>> {
+        self.tmp117.set_high_limit(high_c)
+    }
+
+    /// Set the low limit in celsius while the conversions continue.
+    /// The limit is compared to the next conversion result, an alert already raised stays set until read
+    pub fn set_low_limit(&mut self, low_c: f32) -> Result<(), Error<E>> {
+        self.tmp117.set_low_limit(low_c)
+    }
+
+    /// Set the average while the conversions continue, the cycle time used by the synchronized reads is updated.
+    /// The conversion in progress completes with the previous average, so the next sample may still reflect it
+    pub fn set_average(&mut self, average: Average) -> Result<(), Error<E>> {
+        self.tmp117.set_average(average)?;
+        if let Some(config) = self.tmp117.config {
+            self.cycle_ms = config.conversion().cycle_time_us(average) / 1000;
+        }
+        Ok(())
+    }
+
+    /// Set the conversion cycle while the conversions continue, the cycle time used by the synchronized reads is updated.
+    /// The conversion in progress completes with the previous cycle
+    pub fn set_conversion(&mut self, conversion: Conversion) -> Result<(), Error<E>> {
+        self.tmp117.set_conversion(conversion)?;
+        if let Some(config) = self.tmp117.config {
+            self.cycle_ms = conversion.cycle_time_us(config.average()) / 1000;
+        }
+        Ok(())
+    }
+
+    /// Returns the [stats](SessionStats) of the temperatures read since the start of the session
+    pub fn stats(&self) -> SessionStats {
+        self.tmp117.session.stats()
+    }
+
+    /// Check if an alert was triggered since the last calll
+    pub fn get_alert(&mut self) -> Result<Alert, Error<E>> {
+        let val = self.tmp117.poll_alert()?;
+        Ok(val)
+    }
+
+    /// Wait for an alert to come and return it's value
+    pub fn wait_alert(&mut self) -> Result<Alert, Error<E>> {
+        let val = self.tmp117.wait_for_alert()?;
+        Ok(val)
+    }
+
+    /// Same as [wait_temp](Self::wait_temp) but returns [Error::Timeout] if the data is not ready after `timeout_ms`.
+    /// The configuration is polled with the delay, the alert pin is not used
+    pub fn try_wait_temp<D>(&mut self, delay: &mut D, timeout_ms: u32) -> Result<Celsius, Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.tmp117
+            .wait_for_data_timeout(delay, timeout_ms.saturating_mul(1000))?;
+        self.tmp117.read_temp_raw()
+    }
+
+    /// Same as [wait_alert](Self::wait_alert) but returns [Error::Timeout] if no alert came after `timeout_ms`.
+    /// The configuration is polled with the delay, the alert pin is not used
+    pub fn try_wait_alert<D>(&mut self, delay: &mut D, timeout_ms: u32) -> Result<Alert, Error<E>>
+    where
+        D: DelayNs,
+    {
+        self.tmp117
+            .wait_for_alert_timeout(delay, timeout_ms.saturating_mul(1000))
+    }
+
+    /// Returns an iterator over the temperatures, every call to `next` does a [wait_temp](Self::wait_temp).
+    /// The iterator never ends, use [take](Iterator::take) or break out of the loop to stop.
+    /// The device is put back in shutdown when the iterator is dropped
+    pub fn readings(self) -> Readings<'a, ADDR, T, E, P, O, K> {
+        Readings { handler: self }
+    }
+}
+
+/// Iterator over the temperatures of the continuous mode, see [ContinuousHandler::readings]
+pub struct Readings<'a, const ADDR: u8, T, E, P = DummyPin, O = fn(f32), K = fn(f32) -> f32>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: InputPin,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    handler: ContinuousHandler<'a, ADDR, T, E, P, O, K>,
+}
+
+impl<'a, const ADDR: u8, T, E, P, O, K> Iterator for Readings<'a, ADDR, T, E, P, O, K>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: InputPin,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    type Item = Result<Celsius, Error<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.handler.wait_temp())
     }
 }
@@ -1,13 +1,71 @@
-//! The definitions of drivers of the TMP117
+//! The registers of the TMP117, the single definition shared by the blocking and async drivers
 #![allow(clippy::identity_op)]
 #![allow(missing_docs)]
 
+use core::fmt;
+
 use bilge::prelude::*;
 use device_register::{RERegister, RORegister, RWRegister};
 
 /// The address of the register
 pub struct Address(pub u8);
 
+/// Identifies a register of the device, allows to work with registers without their type
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RegisterId {
+    /// The [Temperature] register
+    Temperature = 0x00,
+
+    /// The [Configuration] register
+    Configuration = 0x01,
+
+    /// The [HighLimit] register
+    HighLimit = 0x02,
+
+    /// The [LowLimit] register
+    LowLimit = 0x03,
+
+    /// The [EEPROM] register
+    EEPROM = 0x04,
+
+    /// The [UEEPROM1] register
+    UEEPROM1 = 0x05,
+
+    /// The [UEEPROM2] register
+    UEEPROM2 = 0x06,
+
+    /// The [UEEPROM3] register
+    UEEPROM3 = 0x07,
+
+    /// The [TemperatureOffset] register
+    TemperatureOffset = 0x08,
+
+    /// The [DeviceID] register
+    DeviceID = 0x0F,
+}
+
+impl RegisterId {
+    /// All the registers of the device in address order
+    pub const ALL: [RegisterId; 10] = [
+        RegisterId::Temperature,
+        RegisterId::Configuration,
+        RegisterId::HighLimit,
+        RegisterId::LowLimit,
+        RegisterId::EEPROM,
+        RegisterId::UEEPROM1,
+        RegisterId::UEEPROM2,
+        RegisterId::UEEPROM3,
+        RegisterId::TemperatureOffset,
+        RegisterId::DeviceID,
+    ];
+
+    /// The address of the register
+    pub fn address(self) -> u8 {
+        self as u8
+    }
+}
+
 /// Temperature register. The value is in 1/7.8125 m°C.
 /// Following a reset, the temperature register reads –256 °C until the first conversion,
 /// including averaging, is complete. Is in two complements
@@ -17,6 +75,23 @@ pub struct Address(pub u8);
 #[register(ty = "Address", addr = "Address(0x00)")]
 pub struct Temperature(pub u16);
 
+impl Temperature {
+    /// The temperature in celsius, the register is sign extended before scaling
+    pub fn as_celsius(self) -> f32 {
+        crate::counts_to_celsius(u16::from(self))
+    }
+
+    /// The temperature in fahrenheit
+    pub fn as_fahrenheit(self) -> f32 {
+        crate::celsius_to_fahrenheit(self.as_celsius())
+    }
+
+    /// The temperature in kelvin
+    pub fn as_kelvin(self) -> f32 {
+        crate::celsius_to_kelvin(self.as_celsius())
+    }
+}
+
 /// Represent the dataready or alert pin select
 #[bitsize(1)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -32,9 +107,11 @@ pub enum AlertPinSelect {
 /// Possible polarities
 #[bitsize(1)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[derive(Copy, Clone, PartialEq, Eq, Debug, FromBits)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, FromBits)]
 pub enum Polarity {
     ///Polarity set to active low
+    #[default]
     ActiveLow = 0,
 
     ///Polarity set to active high
@@ -44,6 +121,7 @@ pub enum Polarity {
 /// Possible mode selection
 #[bitsize(1)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Copy, Clone, PartialEq, Eq, Debug, FromBits)]
 pub enum TriggerMode {
     /// Alert mode
@@ -59,9 +137,11 @@ pub enum TriggerMode {
 /// accumulated average and not a running average.
 #[bitsize(2)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[derive(Copy, Clone, PartialEq, Eq, Debug, FromBits)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, FromBits)]
 pub enum Average {
     /// No averaging
+    #[default]
     NoAverage = 0,
 
     /// 8 averaged conversions
@@ -74,9 +154,16 @@ pub enum Average {
     Avg64 = 3,
 }
 
-impl Default for Average {
-    fn default() -> Self {
-        Self::NoAverage
+impl Average {
+    /// Active conversion time in microseconds, including the averaging.
+    /// This is the time taken by a oneshot and the minimum cycle time in continuous mode
+    pub fn conversion_time_us(self) -> u32 {
+        match self {
+            Average::NoAverage => 15_500,
+            Average::Avg8 => 125_000,
+            Average::Avg32 => 500_000,
+            Average::Avg64 => 1_000_000,
+        }
     }
 }
 
@@ -93,9 +180,11 @@ impl Default for Average {
 /// | 111       | 16 S          | 16 S          | 16 S          | 16 S          |
 #[bitsize(3)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[derive(Copy, Clone, PartialEq, Eq, Debug, FromBits)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, FromBits)]
 pub enum Conversion {
     /// 15.5ms cycle time without average.
+    #[default]
     Ms15_5 = 0,
 
     /// 125ms cycle time without average.
@@ -119,15 +208,34 @@ pub enum Conversion {
     /// 16000ms cycle time without average.
     Ms16000 = 7,
 }
-impl Default for Conversion {
-    fn default() -> Self {
-        Self::Ms15_5
+
+impl Conversion {
+    /// Cycle time in microseconds for the average, see the table of [Conversion]
+    pub fn cycle_time_us(self, average: Average) -> u32 {
+        let cycle = match self {
+            Conversion::Ms15_5 => 15_500,
+            Conversion::Ms125 => 125_000,
+            Conversion::Ms250 => 250_000,
+            Conversion::Ms500 => 500_000,
+            Conversion::Ms1000 => 1_000_000,
+            Conversion::Ms4000 => 4_000_000,
+            Conversion::Ms8000 => 8_000_000,
+            Conversion::Ms16000 => 16_000_000,
+        };
+        // The cycle can't be shorter than the time to do all the averaged conversions
+        cycle.max(average.conversion_time_us())
+    }
+
+    /// Update rate of the temperature in hertz for the average, the inverse of the [cycle time](Self::cycle_time_us)
+    pub fn update_rate_hz(self, average: Average) -> f32 {
+        1_000_000.0 / self.cycle_time_us(average) as f32
     }
 }
 
 /// Conversion mode
 #[bitsize(2)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Copy, Clone, PartialEq, Eq, Debug, TryFromBits)]
 pub enum ConversionMode {
     /// Continuous conversion mode
@@ -197,6 +305,125 @@ pub struct Configuration {
     pub high_alert: bool,
 }
 
+impl Configuration {
+    /// Value of the configuration at power-up with the factory EEPROM
+    pub const FACTORY_DEFAULT: u16 = 0x0220;
+
+    /// Lists the fields that differ from the factory power-up default, like `("mode", "Shutdown")`.
+    /// The status flags and the reset bit are ignored
+    pub fn diff_from_default(&self) -> ConfigDiff {
+        let default = Configuration::try_from(Self::FACTORY_DEFAULT).unwrap();
+        let diff = |name, changed: bool, value| changed.then_some((name, value));
+        ConfigDiff {
+            fields: [
+                diff(
+                    "dr_alert",
+                    self.dr_alert() != default.dr_alert(),
+                    match self.dr_alert() {
+                        AlertPinSelect::Alert => "Alert",
+                        AlertPinSelect::DataReady => "DataReady",
+                    },
+                ),
+                diff(
+                    "polarity",
+                    self.polarity() != default.polarity(),
+                    match self.polarity() {
+                        Polarity::ActiveLow => "ActiveLow",
+                        Polarity::ActiveHigh => "ActiveHigh",
+                    },
+                ),
+                diff(
+                    "trigger_mode",
+                    self.trigger_mode() != default.trigger_mode(),
+                    match self.trigger_mode() {
+                        TriggerMode::Alert => "Alert",
+                        TriggerMode::Thermal => "Thermal",
+                    },
+                ),
+                diff(
+                    "average",
+                    self.average() != default.average(),
+                    match self.average() {
+                        Average::NoAverage => "NoAverage",
+                        Average::Avg8 => "Avg8",
+                        Average::Avg32 => "Avg32",
+                        Average::Avg64 => "Avg64",
+                    },
+                ),
+                diff(
+                    "conversion",
+                    self.conversion() != default.conversion(),
+                    match self.conversion() {
+                        Conversion::Ms15_5 => "Ms15_5",
+                        Conversion::Ms125 => "Ms125",
+                        Conversion::Ms250 => "Ms250",
+                        Conversion::Ms500 => "Ms500",
+                        Conversion::Ms1000 => "Ms1000",
+                        Conversion::Ms4000 => "Ms4000",
+                        Conversion::Ms8000 => "Ms8000",
+                        Conversion::Ms16000 => "Ms16000",
+                    },
+                ),
+                diff(
+                    "mode",
+                    self.mode() != default.mode(),
+                    match self.mode() {
+                        ConversionMode::Continuous => "Continuous",
+                        ConversionMode::Shutdown => "Shutdown",
+                        ConversionMode::OneShot => "OneShot",
+                    },
+                ),
+            ],
+        }
+    }
+}
+
+/// Fields of a [Configuration] that differ from the factory default, as `(field, value)` pairs.
+/// See [Configuration::diff_from_default]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ConfigDiff {
+    fields: [Option<(&'static str, &'static str)>; 6],
+}
+
+impl ConfigDiff {
+    /// Iterate over the fields that differ
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+        self.fields.iter().flatten().copied()
+    }
+
+    /// If the configuration is the factory default
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
+
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "default");
+        }
+        for (i, (name, value)) in self.iter().enumerate() {
+            if i != 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ConfigDiff {
+    fn format(&self, f: defmt::Formatter) {
+        if self.is_empty() {
+            defmt::write!(f, "default");
+        }
+        for (name, value) in self.iter() {
+            defmt::write!(f, "{}: {} ", name, value);
+        }
+    }
+}
+
 /// The high limit register is a 16-bit, read/write register that stores the high limit for comparison with the temperature result.
 /// One LSB equals 7.8125 m°C. The range of the register is ±256 °C. Negative numbers are represented in binary
 /// two's complement format. Following power-up or a general-call reset, the high-limit register is loaded with the
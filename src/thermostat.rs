@@ -0,0 +1,97 @@
+//! Thermostat built on the thermal mode of the device
+use embedded_hal::{
+    digital::InputPin,
+    i2c::{I2c, SevenBitAddress},
+};
+
+use crate::{
+    register::TriggerMode, Alert, Celsius, ContinuousConfig, ContinuousHandler, DummyPin, Error,
+    Tmp117,
+};
+
+/// State of a [Thermostat]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ThermostatState {
+    /// The temperature fell below the setpoint minus the hysteresis and didn't reach the setpoint yet
+    Heating,
+
+    /// The temperature reached the setpoint and didn't fall below the setpoint minus the hysteresis yet
+    Idle,
+}
+
+/// Returns the continuous config of a thermostat, the high limit is the setpoint and the low limit the setpoint minus the hysteresis.
+/// Returns [Error::InvalidLimits] if the hysteresis is negative
+pub(crate) fn thermostat_config<E>(
+    mut config: ContinuousConfig,
+    setpoint_c: f32,
+    hysteresis_c: f32,
+) -> Result<ContinuousConfig, Error<E>> {
+    if hysteresis_c < 0.0 {
+        return Err(Error::InvalidLimits);
+    }
+    config.high = Some(Celsius(setpoint_c));
+    config.low = Some(Celsius(setpoint_c - hysteresis_c));
+    config.trigger_mode = Some(TriggerMode::Thermal);
+    Ok(config)
+}
+
+impl From<Alert> for ThermostatState {
+    /// In thermal mode the high alert flag is set when the temperature goes over the high limit
+    /// and is only cleared when it falls below the low limit
+    fn from(alert: Alert) -> Self {
+        match alert {
+            Alert::High | Alert::HighLow => ThermostatState::Idle,
+            Alert::None | Alert::Low => ThermostatState::Heating,
+        }
+    }
+}
+
+/// Heating thermostat with hysteresis using the thermal mode of the device, see [TriggerMode::Thermal].
+/// The device runs in continuous mode for the lifetime of the thermostat and is shutdown when it is dropped.
+/// The alert flags are polled, the alert pin is not used
+pub struct Thermostat<'a, const ADDR: u8, T, E, P = DummyPin, O = fn(f32), K = fn(f32) -> f32>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: InputPin,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    handler: ContinuousHandler<'a, ADDR, T, E, P, O, K>,
+}
+
+impl<'a, const ADDR: u8, T, E, P, O, K> Thermostat<'a, ADDR, T, E, P, O, K>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error + Copy,
+    P: InputPin,
+    O: FnMut(f32),
+    K: FnMut(f32) -> f32,
+{
+    /// Configure the limits and the thermal mode and start the continuous conversions with the average and conversion of the config.
+    /// The high limit is the setpoint and the low limit the setpoint minus the hysteresis, both in celsius.
+    /// Returns [Error::InvalidLimits] if the hysteresis is negative and [Error::OutOfRange] if a limit is outside of the registers range
+    pub fn new(
+        tmp117: &'a mut Tmp117<ADDR, T, E, P, O, K>,
+        config: ContinuousConfig,
+        setpoint_c: f32,
+        hysteresis_c: f32,
+    ) -> Result<Self, Error<E>> {
+        let config = thermostat_config(config, setpoint_c, hysteresis_c)?;
+        let handler = tmp117.start_continuous(config)?;
+        Ok(Self { handler })
+    }
+
+    /// Read the latched high alert flag and return the state of the thermostat.
+    /// Note that reading the configuration clears the data ready flag
+    pub fn poll(&mut self) -> Result<ThermostatState, Error<E>> {
+        let alert = self.handler.tmp117.poll_alert()?;
+        Ok(alert.into())
+    }
+
+    /// Stop the thermostat and put the device in shutdown
+    pub fn stop(self) -> Result<(), Error<E>> {
+        self.handler.stop()
+    }
+}
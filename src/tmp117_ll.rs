@@ -2,14 +2,16 @@
 use core::marker::PhantomData;
 
 use device_register::{Register, RegisterInterface};
-use embedded_hal::i2c::{I2c, SevenBitAddress};
+use embedded_hal::i2c::{I2c, Operation, SevenBitAddress};
 
 use crate::error::ErrorLL;
-use crate::register::Address;
+use crate::register::{Address, RegisterId};
+use crate::{AddressCheck, RUNTIME_ADDRESS};
 
 /// The low level driver of the TPM117. Allows to read, write and edit the registers directly via the i2c bus
 pub struct Tmp117LL<const ADDR: u8, T, E> {
     i2c: T,
+    addr: u8,
     e: PhantomData<E>,
 }
 
@@ -18,10 +20,107 @@ where
     T: I2c<SevenBitAddress, Error = E>,
     E: embedded_hal::i2c::Error,
 {
-    /// Creates a new instace of the Tmp117 from an i2c bus
+    /// Creates a new instace of the Tmp117 from an i2c bus.
+    /// Doesn't build if the `ADDR` is not one of the [I2cAddress](crate::I2cAddress)
     pub fn new(i2c: T) -> Self {
+        let () = AddressCheck::<ADDR>::VALID;
         Self {
             i2c,
+            addr: ADDR,
+            e: PhantomData,
+        }
+    }
+
+    /// Destroys the driver and returns the i2c bus
+    pub fn release(self) -> T {
+        self.i2c
+    }
+
+    /// Borrow the i2c bus, e.g. for a shared bus
+    pub fn inner(&self) -> &T {
+        &self.i2c
+    }
+
+    /// Mutably borrow the i2c bus, e.g. to probe the bus or do a general call without releasing the driver.
+    /// The caller must not leave the registers of the device in a state the driver doesn't expect
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.i2c
+    }
+
+    /// Read the raw value of the register at the address, an escape hatch for the registers or bits not modeled by the typed registers.
+    /// The address is not validated
+    pub fn read_raw(&mut self, addr: u8) -> Result<u16, ErrorLL<E>> {
+        let mut buff = [0; 2];
+        self.read_raw_with(addr, &mut buff)
+    }
+
+    /// Read the raw value of the register at the address using the buffer for the transfer.
+    /// Batch reads should reuse a single buffer with this function instead of one per register,
+    /// every buffer alive across an await point grows the size of the future
+    pub(crate) fn read_raw_with(
+        &mut self,
+        addr: u8,
+        buff: &mut [u8; 2],
+    ) -> Result<u16, ErrorLL<E>> {
+        self.i2c
+            .write_read(self.addr, &[addr], buff)
+            .map_err(ErrorLL::Bus)?;
+        Ok(u16::from_be_bytes(*buff))
+    }
+
+    /// Read all the registers of [RegisterId::ALL] in a single bus transaction, the values are in the same order.
+    /// Uses [I2c::transaction] with a write of the pointer followed by a read for every register, so a HAL
+    /// supporting it can pipeline all the transfers, e.g. with DMA, instead of doing ten separate transactions.
+    /// The gain depends on the HAL, one without support will do the operations one by one.
+    /// Unlike the single register reads, all the buffers must be alive for the transaction.
+    ///
+    /// Note that reading the temperature and configuration clears the data ready and alert flags
+    pub fn snapshot(&mut self) -> Result<[u16; RegisterId::ALL.len()], ErrorLL<E>> {
+        let addresses = RegisterId::ALL.map(|r| [r.address()]);
+        let mut buffs = [[0u8; 2]; RegisterId::ALL.len()];
+        {
+            let mut reads = buffs.iter_mut();
+            let mut operations: [Operation<'_>; RegisterId::ALL.len() * 2] =
+                core::array::from_fn(|i| {
+                    if i % 2 == 0 {
+                        Operation::Write(&addresses[i / 2])
+                    } else {
+                        Operation::Read(reads.next().unwrap())
+                    }
+                });
+            self.i2c
+                .transaction(self.addr, &mut operations)
+                .map_err(ErrorLL::Bus)?;
+        }
+        Ok(buffs.map(u16::from_be_bytes))
+    }
+
+    /// Send the I2C general call reset, every device on the bus supporting it resets, not only this one
+    pub fn general_call_reset(&mut self) -> Result<(), ErrorLL<E>> {
+        self.i2c.write(0x00, &[0x06]).map_err(ErrorLL::Bus)
+    }
+
+    /// Write the raw value of the register at the address, an escape hatch for the registers or bits not modeled by the typed registers.
+    /// The address and value are not validated
+    pub fn write_raw(&mut self, addr: u8, value: u16) -> Result<(), ErrorLL<E>> {
+        let packet = value.to_be_bytes();
+        self.i2c
+            .write(self.addr, &[addr, packet[0], packet[1]])
+            .map_err(ErrorLL::Bus)
+    }
+}
+
+impl<T, E> Tmp117LL<RUNTIME_ADDRESS, T, E>
+where
+    T: I2c<SevenBitAddress, Error = E>,
+    E: embedded_hal::i2c::Error,
+{
+    /// Creates a new instace of the Tmp117 from an i2c bus with an address known at runtime, e.g. from a board config.
+    /// The `ADDR` of the driver is the [RUNTIME_ADDRESS] marker and is ignored
+    pub fn new_with_address(i2c: T, address: crate::I2cAddress) -> Self {
+        Self {
+            i2c,
+            addr: address.into(),
             e: PhantomData,
         }
     }
@@ -32,25 +131,17 @@ where
     R: Register<Address = Address> + Clone + TryFrom<u16>,
     u16: From<R>,
     E: embedded_hal::i2c::Error,
-    T: embedded_hal::i2c::I2c + embedded_hal::i2c::ErrorType<Error = E>,
+    T: I2c<SevenBitAddress, Error = E>,
 {
     type Error = ErrorLL<E>;
 
     fn read_register(&mut self) -> Result<R, Self::Error> {
-        let mut buff = [0; 2];
-        self.i2c
-            .write_read(ADDR, &[R::ADDRESS.0], &mut buff)
-            .map_err(ErrorLL::Bus)?;
-        let val = u16::from_be_bytes(buff[0..2].try_into().unwrap());
+        let val = self.read_raw(R::ADDRESS.0)?;
         R::try_from(val).map_err(|_| ErrorLL::InvalidData)
     }
 
     fn write_register(&mut self, register: &R) -> Result<(), Self::Error> {
         let val: u16 = register.clone().into();
-        let packet = val.to_be_bytes();
-
-        self.i2c
-            .write(ADDR, &[R::ADDRESS.0, packet[0], packet[1]])
-            .map_err(ErrorLL::Bus)
+        self.write_raw(R::ADDRESS.0, val)
     }
 }
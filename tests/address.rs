@@ -0,0 +1,43 @@
+mod common;
+
+use common::{block_on, MockI2c};
+use tmp117::I2cAddress;
+
+#[test]
+fn address_from_u8() {
+    assert_eq!(I2cAddress::from_u8(0x48), Some(I2cAddress::Gnd));
+    assert_eq!(I2cAddress::from_u8(0x4B), Some(I2cAddress::Scl));
+    assert_eq!(I2cAddress::from_u8(0x47), None);
+    assert_eq!(I2cAddress::from_u8(0x4C), None);
+    assert_eq!(u8::from(I2cAddress::Vcc), 0x49);
+}
+
+#[test]
+fn address_as_const_generic() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<{ I2cAddress::Vcc as u8 }, _, _>::new(&mut bus);
+    assert_eq!(tmp.id().unwrap().device, 0x117);
+}
+
+#[test]
+fn runtime_address() {
+    let mut bus = MockI2c::new();
+    let address = I2cAddress::from_u8(common::ADDR).unwrap();
+    let mut tmp = tmp117::Tmp117::new_with_address(&mut bus, address);
+    assert_eq!(tmp.id().unwrap().device, 0x117);
+}
+
+#[test]
+fn runtime_address_async() {
+    let mut bus = MockI2c::new();
+    let mut other = MockI2c::new();
+    let address = I2cAddress::from_u8(common::ADDR).unwrap();
+    // Drivers with runtime addresses share the same type
+    let mut tmps = [
+        tmp117::asynchronous::Tmp117::new_with_address(&mut bus, address),
+        tmp117::asynchronous::Tmp117::new_with_address(&mut other, address),
+    ];
+    for tmp in tmps.iter_mut() {
+        assert_eq!(block_on(tmp.id()).unwrap().device, 0x117);
+    }
+}
@@ -0,0 +1,192 @@
+mod common;
+
+use common::{block_on, MockDelay, MockI2c, MockPin, Op, Wait, ADDR};
+use tmp117::{asynchronous::Tmp117, register::Average};
+
+const DR_ALERT: u16 = 1 << 2;
+const DATA_READY: u16 = 1 << 13;
+const HIGH_ALERT: u16 = 1 << 15;
+
+#[test]
+fn continuous_transitions() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY | HIGH_ALERT;
+    let mut pin = MockPin::default();
+    let mut tmp = Tmp117::<ADDR, _, _, _>::new_alert(&mut bus, &mut pin);
+
+    block_on(tmp.continuous(Default::default(), |mut t| async move {
+        t.wait_temp().await?;
+        t.wait_temp().await?;
+        t.wait_alert().await?;
+        t.wait_alert().await?;
+        Ok(t)
+    }))
+    .unwrap();
+
+    let config = bus.writes(0x01);
+    assert_eq!(config.len(), 3);
+    // Unknown -> DataReady in the same write as the mode change
+    assert_ne!(config[0] & DR_ALERT, 0);
+    // DataReady -> Alert on the first wait_alert only
+    assert_eq!(config[1] & DR_ALERT, 0);
+    // Shutdown
+    assert_eq!(config[2] & DR_ALERT, 0);
+
+    assert_eq!(
+        bus.ops,
+        [
+            Op::Read(0x01),
+            Op::Write(0x01, config[0]),
+            // The wait_temp only read the temperature once the pin is active, the config isn't read nor rewritten
+            Op::Read(0x00),
+            Op::Read(0x00),
+            // First wait_alert switches to alert
+            Op::Read(0x01),
+            Op::Write(0x01, config[1]),
+            Op::Read(0x01),
+            // Second wait_alert doesn't rewrite the config
+            Op::Read(0x01),
+            Op::Read(0x01),
+            Op::Write(0x01, config[2]),
+        ]
+    );
+    assert_eq!(pin.waits, [Wait::Low; 4]);
+}
+
+#[test]
+fn oneshot_transitions() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY;
+    let mut pin = MockPin::default();
+    let mut tmp = Tmp117::<ADDR, _, _, _>::new_alert(&mut bus, &mut pin);
+
+    block_on(tmp.oneshot(Average::NoAverage)).unwrap();
+    block_on(tmp.oneshot(Average::NoAverage)).unwrap();
+
+    let config = bus.writes(0x01);
+    // Data ready selected once, then a oneshot and a shutdown for each read
+    assert_eq!(config.len(), 5);
+    assert_ne!(config[0] & DR_ALERT, 0);
+    assert!(config.iter().all(|c| c & DR_ALERT != 0));
+    assert_eq!(pin.waits, [Wait::Low; 2]);
+}
+
+#[test]
+fn general_call_reset_forgets_pin_function() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY;
+    let mut delay = MockDelay::default();
+    let mut pin = MockPin::default();
+    let mut tmp = Tmp117::<ADDR, _, _, _>::new_alert(&mut bus, &mut pin);
+
+    block_on(tmp.oneshot(Average::NoAverage)).unwrap();
+    block_on(tmp.general_call_reset(&mut delay)).unwrap();
+    block_on(tmp.oneshot(Average::NoAverage)).unwrap();
+
+    // The EEPROM reload cleared the data ready function of the pin, it is selected again
+    let config = bus.writes(0x01);
+    assert_eq!(config.len(), 6);
+    assert!(config.iter().all(|c| c & DR_ALERT != 0));
+    assert_eq!(delay.elapsed_ns, 2_000_000);
+}
+
+#[test]
+fn reset_forgets_pin_function() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY;
+    let mut delay = MockDelay::default();
+    let mut pin = MockPin::default();
+    let mut tmp = Tmp117::<ADDR, _, _, _>::new_alert(&mut bus, &mut pin);
+
+    block_on(tmp.oneshot(Average::NoAverage)).unwrap();
+    block_on(tmp.reset(&mut delay)).unwrap();
+    block_on(tmp.oneshot(Average::NoAverage)).unwrap();
+
+    // The reset and the shutdown after it, then data ready is selected again for the second oneshot
+    let config = bus.writes(0x01);
+    assert_eq!(config.len(), 8);
+    assert_eq!(config[4] & DR_ALERT, 0);
+    assert!(config[5..].iter().all(|c| c & DR_ALERT != 0));
+}
+
+#[test]
+fn sync_continuous_transitions() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY | HIGH_ALERT;
+    let mut pin = MockPin::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _, _>::new_with_alert(&mut bus, &mut pin);
+
+    tmp.continuous(Default::default(), |mut t| {
+        t.wait_temp()?;
+        t.wait_temp()?;
+        t.wait_alert()?;
+        t.wait_alert()?;
+        Ok(())
+    })
+    .unwrap();
+
+    let config = bus.writes(0x01);
+    assert_eq!(config.len(), 3);
+    // Data ready when entering continuous
+    assert_ne!(config[0] & DR_ALERT, 0);
+    // DataReady -> Alert on the first wait_alert only
+    assert_eq!(config[1] & DR_ALERT, 0);
+    // Shutdown
+    assert_eq!(config[2] & DR_ALERT, 0);
+    assert_eq!(pin.waits, [Wait::Low; 4]);
+}
+
+#[test]
+fn continuous_config_transactions() {
+    use tmp117::{register::TriggerMode, ContinuousConfig};
+
+    let config = ContinuousConfig {
+        trigger_mode: Some(TriggerMode::Alert),
+        ..Default::default()
+    };
+    let mut bus = MockI2c::new();
+    let mut pin = MockPin::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _, _>::new_with_alert(&mut bus, &mut pin);
+    drop(tmp.start_continuous(config).unwrap());
+    // The read clears a stale data ready flag keeping the pin active
+    assert_eq!(bus.ops[..2], [Op::Read(0x01), Op::Write(0x01, DR_ALERT)]);
+
+    let mut bus = MockI2c::new();
+    let mut pin = MockPin::default();
+    let mut tmp = Tmp117::<ADDR, _, _, _>::new_alert(&mut bus, &mut pin);
+    let handler = block_on(tmp.start_continuous(config)).unwrap();
+    block_on(handler.stop()).unwrap();
+    // Data ready selected in the same write as the mode
+    assert_eq!(bus.ops[..2], [Op::Read(0x01), Op::Write(0x01, DR_ALERT)]);
+
+    // Without a pin every field is known once the driver wrote the configuration
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.set_shutdown().unwrap();
+    drop(tmp.start_continuous(config).unwrap());
+    assert_eq!(
+        bus.ops[..3],
+        [Op::Read(0x01), Op::Write(0x01, 0x0620), Op::Write(0x01, 0)]
+    );
+}
+
+#[test]
+fn wait_temp_skips_config_with_pin() {
+    let mut bus = MockI2c::new();
+    let mut pin = MockPin::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _, _>::new_with_alert(&mut bus, &mut pin);
+    tmp.continuous(Default::default(), |mut t| {
+        t.wait_temp()?;
+        Ok(())
+    })
+    .unwrap();
+    // The data ready flag is never set on the bus, only the pin reported it
+    assert_eq!(
+        bus.ops.iter().filter(|op| **op == Op::Read(0x01)).count(),
+        2
+    );
+    assert_eq!(
+        bus.ops.iter().filter(|op| **op == Op::Read(0x00)).count(),
+        1
+    );
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "embedded-hal-bus")]
+
+mod common;
+
+use core::cell::RefCell;
+
+use common::{MockI2c, ADDR};
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+use tmp117::{array::Tmp117Array, Error, I2cAddress};
+
+const DATA_READY: u16 = 1 << 13;
+
+/// A bus with a device at each address, `None` when it isn't populated
+struct Board([Option<MockI2c>; 4]);
+
+impl ErrorType for Board {
+    type Error = ErrorKind;
+}
+
+impl I2c for Board {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        match &mut self.0[(address - 0x48) as usize] {
+            Some(dev) => dev.transaction(ADDR, operations),
+            None => Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+        }
+    }
+}
+
+fn device(raw: u16) -> Option<MockI2c> {
+    let mut bus = MockI2c::new();
+    bus.registers[0x00] = raw;
+    bus.flags_on_read = DATA_READY;
+    Some(bus)
+}
+
+#[test]
+fn read_all() {
+    let bus = RefCell::new(Board([
+        device(0x0C80),
+        None,
+        device(0x0080),
+        device(0xFF00),
+    ]));
+    let mut array = Tmp117Array::new(&bus);
+    assert_eq!(
+        array.read_all(),
+        [
+            Ok(25.0),
+            Err(Error::Bus(ErrorKind::NoAcknowledge(
+                NoAcknowledgeSource::Address
+            ))),
+            Ok(1.0),
+            Ok(-2.0),
+        ]
+    );
+    assert_eq!(array.get_mut(I2cAddress::Scl).id().unwrap().device, 0x117);
+}
@@ -0,0 +1,266 @@
+//! In-memory TMP117 used by the integration tests
+#![allow(dead_code)]
+
+use core::{
+    convert::Infallible,
+    future::Future,
+    pin::pin,
+    ptr,
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+
+use embedded_hal::i2c::{ErrorKind, ErrorType, NoAcknowledgeSource, Operation, SevenBitAddress};
+
+/// Address used by the tests
+pub const ADDR: u8 = 0x49;
+
+/// A bus transaction as seen by the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// A register was read
+    Read(u8),
+
+    /// A register was written with a value
+    Write(u8, u16),
+
+    /// A general call reset was received
+    GeneralCallReset,
+}
+
+/// Mock of the TMP117 register map, records every register access
+pub struct MockI2c {
+    pub registers: [u16; 16],
+    pub ops: Vec<Op>,
+    pub bytes: Vec<Vec<u8>>,
+    /// Flags of the configuration set on every read, simulates conversions and alerts happening
+    pub flags_on_read: u16,
+    /// Non volatile copy of the registers, loaded on power cycle
+    pub eeprom: [u16; 16],
+    /// Indexes of the transactions answered with a NACK
+    pub nacks: Vec<usize>,
+    /// Number of transactions received
+    pub transactions: usize,
+}
+
+impl MockI2c {
+    /// Creates a device with the registers in their reset state
+    pub fn new() -> Self {
+        let mut registers = [0; 16];
+        registers[0x00] = 0x8000;
+        registers[0x01] = 0x0220;
+        registers[0x02] = 0x6000;
+        registers[0x03] = 0x8000;
+        registers[0x0F] = 0x0117;
+        Self {
+            registers,
+            ops: Vec::new(),
+            bytes: Vec::new(),
+            flags_on_read: 0,
+            eeprom: registers,
+            nacks: Vec::new(),
+            transactions: 0,
+        }
+    }
+
+    /// Reload the EEPROM backed registers like a power-up would
+    pub fn power_cycle(&mut self) {
+        for reg in [0x01, 0x02, 0x03, 0x05, 0x06, 0x07, 0x08] {
+            self.registers[reg] = self.eeprom[reg];
+        }
+        self.registers[0x00] = 0x8000;
+        self.registers[0x04] = 0;
+    }
+
+    /// Returns the writes done to a register
+    pub fn writes(&self, reg: u8) -> Vec<u16> {
+        self.ops
+            .iter()
+            .filter_map(|op| match op {
+                Op::Write(r, v) if *r == reg => Some(*v),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn process(&mut self, address: u8, operations: &mut [Operation<'_>]) {
+        if address == 0x00 {
+            assert!(matches!(operations, [Operation::Write(&[0x06])]));
+            self.ops.push(Op::GeneralCallReset);
+            self.power_cycle();
+            return;
+        }
+        assert_eq!(address, ADDR);
+        let mut pointer = 0;
+        for op in operations {
+            match op {
+                Operation::Write(data) => {
+                    self.bytes.push(data.to_vec());
+                    pointer = data[0];
+                    if data.len() == 3 {
+                        let val = u16::from_be_bytes([data[1], data[2]]);
+                        self.ops.push(Op::Write(pointer, val));
+                        // Soft reset
+                        if pointer == 0x01 && val & 0x0002 != 0 {
+                            self.power_cycle();
+                            continue;
+                        }
+                        let val = match pointer {
+                            // Only the mode and settings are writable, reset clears itself
+                            0x01 => val & 0x0FFC,
+                            // Only the unlock bit is writable
+                            0x04 => val & 0x8000,
+                            _ => val,
+                        };
+                        self.registers[pointer as usize] = val;
+                        let unlocked = self.registers[0x04] & 0x8000 != 0;
+                        if unlocked && matches!(pointer, 0x01..=0x03 | 0x05..=0x08) {
+                            self.eeprom[pointer as usize] = val;
+                        }
+                    }
+                }
+                Operation::Read(buf) => {
+                    self.ops.push(Op::Read(pointer));
+                    if pointer == 0x01 {
+                        self.registers[0x01] |= self.flags_on_read;
+                    }
+                    let val = self.registers[pointer as usize];
+                    buf.copy_from_slice(&val.to_be_bytes());
+                    // Data ready and alert flags are cleared on read of the configuration
+                    if pointer == 0x01 {
+                        self.registers[0x01] &= !0xE000;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ErrorType for MockI2c {
+    type Error = ErrorKind;
+}
+
+impl embedded_hal::i2c::I2c<SevenBitAddress> for MockI2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.transactions += 1;
+        if self.nacks.contains(&(self.transactions - 1)) {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        self.process(address, operations);
+        Ok(())
+    }
+}
+
+impl embedded_hal_async::i2c::I2c<SevenBitAddress> for MockI2c {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.transactions += 1;
+        if self.nacks.contains(&(self.transactions - 1)) {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        self.process(address, operations);
+        Ok(())
+    }
+}
+
+/// Mock of the alert pin, records the waits done on it
+#[derive(Default)]
+pub struct MockPin {
+    pub waits: Vec<Wait>,
+}
+
+/// A wait done on the alert pin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wait {
+    High,
+    Low,
+    RisingEdge,
+    FallingEdge,
+    AnyEdge,
+}
+
+impl embedded_hal::digital::ErrorType for MockPin {
+    type Error = Infallible;
+}
+
+impl embedded_hal_async::digital::Wait for MockPin {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        self.waits.push(Wait::High);
+        Ok(())
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        self.waits.push(Wait::Low);
+        Ok(())
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.waits.push(Wait::RisingEdge);
+        Ok(())
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.waits.push(Wait::FallingEdge);
+        Ok(())
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        self.waits.push(Wait::AnyEdge);
+        Ok(())
+    }
+}
+
+/// The level of the pin is always the one checked, as if the pin was already active
+impl embedded_hal::digital::InputPin for MockPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        self.waits.push(Wait::High);
+        Ok(true)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        self.waits.push(Wait::Low);
+        Ok(true)
+    }
+}
+
+/// Drives a future that never waits on anything external to completion
+pub fn block_on<F: Future>(fut: F) -> F::Output {
+    fn clone(_: *const ()) -> RawWaker {
+        RawWaker::new(ptr::null(), &VTABLE)
+    }
+    fn noop(_: *const ()) {}
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+
+    let waker = unsafe { Waker::from_raw(RawWaker::new(ptr::null(), &VTABLE)) };
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = pin!(fut);
+    loop {
+        if let Poll::Ready(val) = fut.as_mut().poll(&mut cx) {
+            return val;
+        }
+    }
+}
+
+/// Delay that only records the time waited
+#[derive(Default)]
+pub struct MockDelay {
+    pub elapsed_ns: u64,
+}
+
+impl embedded_hal::delay::DelayNs for MockDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.elapsed_ns += ns as u64;
+    }
+}
+
+impl embedded_hal_async::delay::DelayNs for MockDelay {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.elapsed_ns += ns as u64;
+    }
+}
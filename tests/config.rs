@@ -0,0 +1,178 @@
+mod common;
+
+use common::{block_on, MockDelay, MockI2c, MockPin, Op, ADDR};
+use tmp117::{
+    register::{Average, Conversion, ConversionMode},
+    Error,
+};
+
+#[test]
+fn set_average_and_conversion_keep_mode() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.set_conversion(Conversion::Ms125).unwrap();
+    tmp.set_average(Average::NoAverage).unwrap();
+    assert_eq!(bus.registers[0x01], 0x0080);
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.set_conversion(Conversion::Ms1000)).unwrap();
+    assert_eq!(bus.registers[0x01], 0x0200);
+}
+
+#[test]
+fn get_average_and_conversion() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(tmp.get_average().unwrap(), Average::Avg8);
+    assert_eq!(tmp.get_conversion().unwrap(), Conversion::Ms1000);
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.set_average(Average::Avg64)).unwrap();
+    assert_eq!(block_on(tmp.get_average()).unwrap(), Average::Avg64);
+}
+
+#[test]
+fn oneshot_with_conversion() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = 1 << 13;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.oneshot_with(Average::NoAverage, Conversion::Ms125)
+        .unwrap();
+    // Oneshot mode with the conversion cycle and no average
+    assert_eq!(bus.writes(0x01)[0] & 0x0FFC, 0x0C80);
+}
+
+#[test]
+fn current_mode() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(tmp.current_mode(), Ok(ConversionMode::Continuous));
+    tmp.set_shutdown().unwrap();
+    assert_eq!(tmp.current_mode(), Ok(ConversionMode::Shutdown));
+
+    // Reserved mode bits
+    bus.registers[0x01] = 0x0800;
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    assert_eq!(block_on(tmp.current_mode()), Err(Error::InvalidMode(0b10)));
+}
+
+#[test]
+fn use_cached_config() {
+    let mut bus = MockI2c::new();
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.set_use_cached_config(true);
+    tmp.set_shutdown().unwrap();
+    tmp.set_shutdown().unwrap();
+    // The device leaves shutdown on reset, the cache is invalidated
+    tmp.reset(&mut delay).unwrap();
+    tmp.set_shutdown().unwrap();
+    // A single shutdown and the reset, the configuration is only read when the cache is unknown
+    assert_eq!(bus.writes(0x01).len(), 3);
+    assert_eq!(
+        bus.ops.iter().filter(|op| **op == Op::Read(0x01)).count(),
+        2
+    );
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.set_shutdown()).unwrap();
+    block_on(tmp.set_shutdown()).unwrap();
+    assert_eq!(bus.writes(0x01).len(), 5);
+}
+
+#[test]
+fn config_transaction() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.config_transaction(|c| {
+        c.set_mode(ConversionMode::Shutdown);
+        c.set_average(Average::Avg32);
+        c.set_conversion(Conversion::Ms1000);
+    })
+    .unwrap();
+    tmp.set_use_cached_config(true);
+    tmp.config_transaction(|c| c.set_average(Average::Avg64))
+        .unwrap();
+    assert_eq!(bus.writes(0x01), [0x0640, 0x0660]);
+    assert_eq!(
+        bus.ops.iter().filter(|op| **op == Op::Read(0x01)).count(),
+        1
+    );
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.config_transaction(|c| c.set_average(Average::NoAverage))).unwrap();
+    assert_eq!(bus.registers[0x01], 0x0600);
+}
+
+#[test]
+fn reserved_mode_is_reported() {
+    let mut bus = MockI2c::new();
+    bus.registers[0x01] = 0x0800;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(
+        tmp.set_average(Average::Avg8),
+        Err(Error::InvalidMode(0b10))
+    );
+    assert_eq!(
+        tmp.continuous(Default::default(), |_| Ok(())).err(),
+        Some(Error::InvalidMode(0b10))
+    );
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    assert_eq!(
+        block_on(tmp.oneshot(Average::NoAverage)),
+        Err(Error::InvalidMode(0b10))
+    );
+}
+
+#[test]
+fn update_rate() {
+    assert_eq!(Conversion::Ms500.update_rate_hz(Average::NoAverage), 2.0);
+    assert_eq!(Conversion::Ms16000.update_rate_hz(Average::Avg8), 0.0625);
+    // Limited by the averaged conversions
+    assert_eq!(Conversion::Ms15_5.update_rate_hz(Average::Avg64), 1.0);
+}
+
+#[test]
+fn cached_config_ignored_with_pin() {
+    let mut bus = MockI2c::new();
+    let mut pin = MockPin::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _, _>::new_with_alert(&mut bus, &mut pin);
+    tmp.set_use_cached_config(true);
+    tmp.set_shutdown().unwrap();
+    tmp.set_average(Average::Avg64).unwrap();
+    // Every edit reads the configuration, clearing a stale data ready flag
+    assert_eq!(
+        bus.ops.iter().filter(|op| **op == Op::Read(0x01)).count(),
+        2
+    );
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new_alert(&mut bus, &mut pin);
+    tmp.set_use_cached_config(true);
+    block_on(tmp.set_shutdown()).unwrap();
+    block_on(tmp.set_shutdown()).unwrap();
+    assert_eq!(
+        bus.ops.iter().filter(|op| **op == Op::Read(0x01)).count(),
+        4
+    );
+}
+
+#[test]
+fn health_check_reset_before_mode() {
+    let mut bus = MockI2c::new();
+    let mut delay = MockDelay::default();
+    // Mid-reset with garbage in the mode bits
+    bus.registers[0x01] = 0x0802;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(tmp.health_check(&mut delay), Err(Error::ResetInProgress));
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    assert_eq!(
+        block_on(tmp.health_check(&mut delay)),
+        Err(Error::ResetInProgress)
+    );
+
+    bus.registers[0x01] = 0x0800;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(tmp.health_check(&mut delay), Err(Error::InvalidMode(0b10)));
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use common::{block_on, MockI2c, ADDR};
+use tmp117::register::{Average, Configuration, Conversion, Polarity, TriggerMode};
+
+fn config(bus: &MockI2c) -> Configuration {
+    Configuration::try_from(bus.registers[0x01]).unwrap()
+}
+
+#[test]
+fn persist_config() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.persist_config(
+        Average::Avg8,
+        Conversion::Ms500,
+        Polarity::ActiveHigh,
+        TriggerMode::Thermal,
+    )
+    .unwrap();
+
+    // Locked back after programming
+    assert_eq!(bus.registers[0x04] & 0x8000, 0);
+
+    bus.power_cycle();
+    let config = config(&bus);
+    assert_eq!(config.average(), Average::Avg8);
+    assert_eq!(config.conversion(), Conversion::Ms500);
+    assert_eq!(config.polarity(), Polarity::ActiveHigh);
+    assert_eq!(config.trigger_mode(), TriggerMode::Thermal);
+}
+
+#[test]
+fn persist_config_async() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.persist_config(
+        Average::Avg8,
+        Conversion::Ms500,
+        Polarity::ActiveLow,
+        TriggerMode::Alert,
+    ))
+    .unwrap();
+
+    bus.power_cycle();
+    let config = config(&bus);
+    assert_eq!(config.average(), Average::Avg8);
+    assert_eq!(config.conversion(), Conversion::Ms500);
+}
+
+#[test]
+fn save_config_to_eeprom() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.set_offset(-0.5).unwrap();
+    tmp.set_high_limit(40.0).unwrap();
+    tmp.set_average(Average::Avg32).unwrap();
+    tmp.save_config_to_eeprom().unwrap();
+    assert_eq!(bus.registers[0x04] & 0x8000, 0);
+
+    bus.power_cycle();
+    assert_eq!(config(&bus).average(), Average::Avg32);
+    assert_eq!(bus.registers[0x02], 0x1400);
+    assert_eq!(bus.registers[0x08] as i16, -64);
+}
+
+#[test]
+fn save_config_to_eeprom_async() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.set_low_limit(-10.0)).unwrap();
+    block_on(tmp.save_config_to_eeprom()).unwrap();
+
+    bus.power_cycle();
+    assert_eq!(bus.registers[0x03] as i16, -1280);
+}
+
+#[test]
+fn single_user_eeprom() {
+    let mut bus = MockI2c::new();
+    bus.registers[0x05] = 1;
+    bus.registers[0x07] = 3;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.write_user_eeprom(1, 0xBEEF).unwrap();
+    assert_eq!(tmp.read_user_eeprom(1), Ok(0xBEEF));
+    assert_eq!(tmp.read_user_eeprom(3), Err(tmp117::Error::InvalidIndex(3)));
+    assert_eq!(bus.writes(0x05), []);
+    assert_eq!(bus.writes(0x07), []);
+}
+
+#[test]
+fn write_user_eeprom_keeps_nist_id() {
+    let mut bus = MockI2c::new();
+    bus.registers[0x05] = 0x1234;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(
+        tmp.write_user_eeprom(0, 0xBEEF),
+        Err(tmp117::Error::InvalidIndex(0))
+    );
+    assert_eq!(tmp.read_nist_id(), Ok(0x1234));
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    assert_eq!(
+        block_on(tmp.write_user_eeprom(0, 0xBEEF)),
+        Err(tmp117::Error::InvalidIndex(0))
+    );
+    block_on(tmp.write_user_eeprom_including_nist(0, 0xBEEF)).unwrap();
+    assert_eq!(bus.writes(0x05), [0xBEEF]);
+}
+
+#[test]
+fn write_eeprom_keeps_nist_id() {
+    let mut bus = MockI2c::new();
+    bus.registers[0x05] = 0x1234;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.write_eeprom([0, 2, 3]).unwrap();
+    assert_eq!(tmp.read_nist_id(), Ok(0x1234));
+    assert_eq!(tmp.read_eeprom(), Ok([0x1234, 2, 3]));
+}
@@ -0,0 +1,68 @@
+use core::fmt;
+
+use tmp117::{
+    error::{Error, ErrorLL, SimpleError},
+    Alert,
+};
+
+#[derive(Debug)]
+struct BusError;
+
+impl fmt::Display for BusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "nack")
+    }
+}
+
+#[cfg(feature = "error-in-core")]
+impl core::error::Error for BusError {}
+
+#[test]
+fn display() {
+    assert_eq!(
+        Error::<BusError>::DataNotReady.to_string(),
+        "temperature data not ready"
+    );
+    assert_eq!(
+        Error::<BusError>::WrongDevice { found: 0x116 }.to_string(),
+        "device ID 0x0116 is not a tmp117"
+    );
+    assert_eq!(
+        ErrorLL::<BusError>::InvalidData.to_string(),
+        "received invalid data"
+    );
+    assert_eq!(
+        Error::<BusError>::NotYetConverted.to_string(),
+        "no conversion completed since power-up"
+    );
+    assert_eq!(Alert::HighLow.to_string(), "high and low");
+    assert_eq!(Alert::None.to_string(), "none");
+}
+
+#[test]
+fn erase() {
+    assert_eq!(Error::Bus(BusError).erase(), SimpleError::Bus);
+    assert_eq!(
+        Error::<BusError>::WrongDevice { found: 0x116 }.erase(),
+        SimpleError::WrongDevice { found: 0x116 }
+    );
+    let err: SimpleError = Error::<BusError>::InvalidMode(0b10).into();
+    assert_eq!(err, SimpleError::InvalidMode(0b10));
+    assert_eq!(
+        err.to_string(),
+        Error::<BusError>::InvalidMode(0b10).to_string()
+    );
+}
+
+#[cfg(feature = "error-in-core")]
+#[test]
+fn source_is_the_bus_error() {
+    use core::error::Error as _;
+
+    let err: Error<BusError> = Error::Bus(BusError);
+    assert_eq!(err.source().unwrap().to_string(), "nack");
+    assert!(Error::<BusError>::Timeout.source().is_none());
+
+    let err: ErrorLL<BusError> = ErrorLL::Bus(BusError);
+    assert_eq!(err.source().unwrap().to_string(), "nack");
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use common::*;
+use tmp117::{fahrenheit_centi_from_counts, ContinuousConfig, Tmp117, CELCIUS_CONVERSION};
+
+#[test]
+fn matches_float_path_on_rated_range() {
+    // Rated range of the device is -55 °C to 150 °C
+    let min = (-55.0 / CELCIUS_CONVERSION) as i16;
+    let max = (150.0 / CELCIUS_CONVERSION) as i16;
+    for counts in min..=max {
+        let celsius = counts as f64 * CELCIUS_CONVERSION as f64;
+        let expected = (celsius * 9.0 / 5.0 + 32.0) * 100.0;
+        // Within rounding, the float path isn't exact on the halfway values
+        let diff = fahrenheit_centi_from_counts(counts) as f64 - expected;
+        assert!(diff.abs() <= 0.5 + 1e-9, "{counts}: {diff}");
+    }
+}
+
+#[test]
+fn full_range() {
+    assert_eq!(fahrenheit_centi_from_counts(0), 3200);
+    assert_eq!(fahrenheit_centi_from_counts(i16::MAX), 49_279);
+    assert_eq!(fahrenheit_centi_from_counts(i16::MIN), -42_880);
+}
+
+#[test]
+fn handler_reads_fahrenheit() {
+    let mut bus = MockI2c::new();
+    // 25 °C
+    bus.registers[0x00] = 0x0C80;
+    bus.flags_on_read = 0x2000;
+    let mut tmp = Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.continuous(ContinuousConfig::default(), |mut handler| {
+        assert_eq!(handler.read_temp_fahrenheit_centi().unwrap(), 7700);
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::{MockI2c, Op, ADDR};
+use tmp117::OneShotFilter;
+
+#[test]
+fn convergence() {
+    let mut filter = OneShotFilter::new(0.5);
+    assert_eq!(filter.update(20.0), 20.0);
+    assert_eq!(filter.update(30.0), 25.0);
+    assert_eq!(filter.update(30.0), 27.5);
+
+    for _ in 0..32 {
+        filter.update(30.0);
+    }
+    assert!((filter.state.unwrap() - 30.0).abs() < 1e-3);
+}
+
+#[test]
+fn read_averaged() {
+    let mut bus = MockI2c::new();
+    bus.registers[0x00] = 0x0C80;
+    bus.flags_on_read = 1 << 13;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.continuous(Default::default(), |mut t| {
+        assert_eq!(t.read_averaged(4)?, tmp117::Celsius(25.0));
+        Ok(())
+    })
+    .unwrap();
+    let temp_reads = bus.ops.iter().filter(|op| **op == Op::Read(0x00)).count();
+    assert_eq!(temp_reads, 4);
+}
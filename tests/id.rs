@@ -0,0 +1,38 @@
+mod common;
+
+use common::{block_on, MockI2c, ADDR};
+use tmp117::{Error, Id};
+
+#[test]
+fn init_checks_device_id() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(tmp.init().unwrap().device, 0x117);
+
+    // A TMP116 answering at the address
+    bus.registers[0x0F] = 0x1116;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(tmp.init(), Err(Error::WrongDevice { found: 0x116 }));
+}
+
+#[test]
+fn init_checks_device_id_async() {
+    let mut bus = MockI2c::new();
+    bus.registers[0x0F] = 0;
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    assert_eq!(block_on(tmp.init()), Err(Error::WrongDevice { found: 0 }));
+}
+
+#[test]
+fn is_tmp117() {
+    let id = Id {
+        device: Id::TMP117_DEVICE_ID,
+        revision: 3,
+    };
+    assert!(id.is_tmp117());
+    assert!(!Id {
+        device: 0x116,
+        revision: 0
+    }
+    .is_tmp117());
+}
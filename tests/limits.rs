@@ -0,0 +1,173 @@
+mod common;
+
+use common::{block_on, MockI2c, Op, ADDR};
+use tmp117::{Celsius, ContinuousConfig, Error};
+
+#[test]
+fn window_encoding() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.set_window(22.0, 2.0).unwrap();
+    assert_eq!(bus.registers[0x02], 0x0C00);
+    assert_eq!(bus.registers[0x03], 0x0A00);
+}
+
+#[test]
+fn window_negative() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.set_window(-10.0, 2.0).unwrap();
+    assert_eq!(bus.registers[0x02] as i16, -1024);
+    assert_eq!(bus.registers[0x03] as i16, -1536);
+}
+
+#[test]
+fn window_invalid() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(tmp.set_window(22.0, -2.0), Err(Error::InvalidLimits));
+    assert!(bus.ops.is_empty());
+}
+
+#[test]
+fn window_moving_down_writes_low_first() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.set_window(22.0, 2.0).unwrap();
+    tmp.set_window(-10.0, 2.0).unwrap();
+    // The high limit of the new window is below the previous low limit
+    let writes: Vec<_> = bus
+        .ops
+        .iter()
+        .filter(|op| matches!(op, Op::Write(..)))
+        .collect();
+    assert_eq!(
+        writes,
+        [
+            &Op::Write(0x02, 0x0C00),
+            &Op::Write(0x03, 0x0A00),
+            &Op::Write(0x03, (-1536i16) as u16),
+            &Op::Write(0x02, (-1024i16) as u16),
+        ]
+    );
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.set_window(22.0, 2.0)).unwrap();
+    let writes = bus.writes(0x02);
+    assert_eq!(writes.last(), Some(&0x0C00));
+    // Moving up keeps the high limit first
+    assert_eq!(
+        bus.ops.iter().rev().find(|op| matches!(op, Op::Write(..))),
+        Some(&Op::Write(0x03, 0x0A00))
+    );
+}
+
+#[test]
+fn window_encoding_async() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.set_window(22.0, 2.0)).unwrap();
+    assert_eq!(bus.registers[0x02], 0x0C00);
+    assert_eq!(bus.registers[0x03], 0x0A00);
+}
+
+#[test]
+fn continuous_out_of_range() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    let config = ContinuousConfig {
+        high: Some(Celsius(300.0)),
+        ..Default::default()
+    };
+    assert_eq!(tmp.continuous(config, |_| Ok(())), Err(Error::OutOfRange));
+    assert!(bus.ops.is_empty());
+}
+
+#[test]
+fn continuous_in_range() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    let config = ContinuousConfig {
+        high: Some(Celsius(255.99)),
+        ..Default::default()
+    };
+    tmp.continuous(config, |_| Ok(())).unwrap();
+    assert_eq!(bus.registers[0x02], 0x7FFE);
+}
+
+#[test]
+fn continuous_out_of_range_async() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    let config = ContinuousConfig {
+        offset: Some(Celsius(-300.0)),
+        ..Default::default()
+    };
+    let res = block_on(tmp.continuous(config, |t| async { Ok(t) }));
+    assert_eq!(res, Err(Error::OutOfRange));
+    assert!(bus.ops.is_empty());
+}
+
+#[test]
+fn limits_read_back() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.set_high_limit(30.5).unwrap();
+    tmp.set_low_limit(-40.25).unwrap();
+    assert_eq!(tmp.get_high_limit(), Ok(30.5));
+    assert_eq!(tmp.get_low_limit(), Ok(-40.25));
+}
+
+#[test]
+fn limits_read_back_async() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    // Reset values
+    assert_eq!(block_on(tmp.get_high_limit()), Ok(192.0));
+    assert_eq!(block_on(tmp.get_low_limit()), Ok(-256.0));
+}
+
+#[test]
+fn offset_read_back() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.set_offset(-1.5).unwrap();
+    assert_eq!(tmp.get_offset(), Ok(-1.5));
+    assert_eq!(tmp.set_offset(256.0), Err(Error::OutOfRange));
+    assert_eq!(bus.registers[0x08] as i16, -192);
+}
+
+#[test]
+fn continuous_builder() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    let config = ContinuousConfig::builder()
+        .high_limit(30.0)
+        .offset(0.5)
+        .build();
+    assert_eq!(config.low, None);
+    tmp.continuous(config, |_| Ok(())).unwrap();
+    assert_eq!(bus.writes(0x02), [0x0F00]);
+    assert_eq!(bus.writes(0x03), []);
+    assert_eq!(bus.writes(0x08), [0x0040]);
+}
+
+#[test]
+fn clamped_limits() {
+    assert_eq!(
+        Celsius(300.0).clamp_to_range(),
+        Celsius(tmp117::TEMP_MAX_CELSIUS)
+    );
+    assert_eq!(Celsius(-300.0).clamp_to_range(), Celsius(-256.0));
+    assert_eq!(Celsius(25.0).clamp_to_range(), Celsius(25.0));
+
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(tmp.set_high_limit(300.0), Err(Error::OutOfRange));
+    tmp.set_high_limit_clamped(300.0).unwrap();
+    assert_eq!(bus.registers[0x02], 0x7FFF);
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.set_low_limit_clamped(-300.0)).unwrap();
+    assert_eq!(bus.registers[0x03], 0x8000);
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use common::{block_on, MockDelay, MockI2c, MockPin, Op, ADDR};
+use tmp117::register::HighLimit;
+
+#[test]
+fn byte_order() {
+    use device_register::{ReadRegister, WriteRegister};
+
+    let mut bus = MockI2c::new();
+    let mut tmp_ll = tmp117::tmp117_ll::Tmp117LL::<ADDR, _, _>::new(&mut bus);
+    tmp_ll.write(HighLimit::from(0x6000)).unwrap();
+    bus.registers[0x02] = 0x1234;
+
+    let mut tmp_ll = tmp117::tmp117_ll::Tmp117LL::<ADDR, _, _>::new(&mut bus);
+    let high: HighLimit = tmp_ll.read().unwrap();
+    assert_eq!(u16::from(high), 0x1234);
+    assert_eq!(bus.bytes, [vec![0x02, 0x60, 0x00], vec![0x02]]);
+}
+
+#[test]
+fn byte_order_async() {
+    use device_register_async::{ReadRegister, WriteRegister};
+
+    let mut bus = MockI2c::new();
+    let mut tmp_ll = tmp117::asynchronous::tmp117_ll::Tmp117LL::<ADDR, _, _>::new(&mut bus);
+    block_on(tmp_ll.write(HighLimit::from(0x6000))).unwrap();
+    bus.registers[0x02] = 0x1234;
+
+    let mut tmp_ll = tmp117::asynchronous::tmp117_ll::Tmp117LL::<ADDR, _, _>::new(&mut bus);
+    let high: HighLimit = block_on(tmp_ll.read()).unwrap();
+    assert_eq!(u16::from(high), 0x1234);
+    assert_eq!(bus.bytes, [vec![0x02, 0x60, 0x00], vec![0x02]]);
+}
+
+#[test]
+fn release_returns_the_bus() {
+    let mut bus = MockI2c::new();
+    let tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    let bus = tmp.release();
+    bus.registers[0x0F] = 0;
+
+    let tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new_alert(bus, MockPin::default());
+    let (bus, pin) = tmp.release();
+    assert!(pin.is_some());
+    assert_eq!(bus.registers[0x0F], 0);
+}
+
+#[test]
+fn inner_borrows_the_bus() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.inner_mut().registers[0x0F] = 0x1116;
+    assert_eq!(tmp.inner().registers[0x0F], 0x1116);
+    assert_eq!(tmp.id().unwrap().device, 0x116);
+}
+
+#[test]
+fn general_call_reset() {
+    let mut bus = MockI2c::new();
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.set_high_limit(30.0).unwrap();
+    tmp.general_call_reset(&mut delay).unwrap();
+    assert_eq!(tmp.get_high_limit().unwrap(), 192.0);
+    assert!(tmp.cached_config().is_none());
+    assert_eq!(delay.elapsed_ns, 2_000_000);
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.general_call_reset(&mut delay)).unwrap();
+    assert!(bus.ops.contains(&Op::GeneralCallReset));
+}
+
+#[test]
+fn reset_with_delay() {
+    let mut bus = MockI2c::new();
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.reset(&mut delay).unwrap();
+    assert_eq!(delay.elapsed_ns, 2_000_000);
+    tmp.reset_with_delay(&mut delay, 5).unwrap();
+    assert_eq!(delay.elapsed_ns, 7_000_000);
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.reset_with_delay(&mut delay, 3)).unwrap();
+    assert_eq!(delay.elapsed_ns, 10_000_000);
+    assert_eq!(bus.registers[0x01] & 0x0C00, 0x0400);
+}
+
+#[test]
+fn dump_registers() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    let dump = tmp.dump_registers().unwrap();
+    assert_eq!(dump[0], (0x00, 0x8000));
+    assert_eq!(dump[1], (0x01, 0x0220));
+    assert_eq!(dump[9], (0x0F, 0x0117));
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    let dump = block_on(tmp.dump_registers()).unwrap();
+    assert_eq!(dump[8].0, 0x08);
+}
+
+#[test]
+fn raw_access() {
+    let mut bus = MockI2c::new();
+    let mut tmp_ll = tmp117::tmp117_ll::Tmp117LL::<ADDR, _, _>::new(&mut bus);
+    tmp_ll.write_raw(0x06, 0xBEEF).unwrap();
+    assert_eq!(tmp_ll.read_raw(0x06).unwrap(), 0xBEEF);
+
+    let mut tmp_ll = tmp117::asynchronous::tmp117_ll::Tmp117LL::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(block_on(tmp_ll.read_raw(0x0F)).unwrap(), 0x0117);
+    assert_eq!(bus.bytes[0], [0x06, 0xBE, 0xEF]);
+}
+
+#[test]
+fn low_level_access() {
+    use device_register::WriteRegister;
+
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.low_level().write(HighLimit::from(0x0F00)).unwrap();
+    assert_eq!(tmp.get_high_limit().unwrap(), 30.0);
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    let high: HighLimit = {
+        use device_register_async::ReadRegister;
+        block_on(tmp.low_level().read()).unwrap()
+    };
+    assert_eq!(u16::from(high), 0x0F00);
+}
+
+#[test]
+fn reload_eeprom() {
+    let mut bus = MockI2c::new();
+    // Stored in thermal mode with a 30°C high limit
+    bus.eeprom[0x01] = 0x0230;
+    bus.eeprom[0x02] = 0x0F00;
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.set_shutdown().unwrap();
+    tmp.reload_eeprom(&mut delay).unwrap();
+    assert!(tmp.cached_config().is_none());
+    // The stored mode is kept, no shutdown after the reset
+    assert_eq!(bus.registers[0x01], 0x0230);
+    assert_eq!(bus.registers[0x02], 0x0F00);
+    assert_eq!(delay.elapsed_ns, 2_000_000);
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.set_shutdown()).unwrap();
+    block_on(tmp.reload_eeprom(&mut delay)).unwrap();
+    assert_eq!(bus.registers[0x01], 0x0230);
+}
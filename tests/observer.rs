@@ -0,0 +1,42 @@
+mod common;
+
+use core::cell::Cell;
+
+use common::{block_on, MockI2c, ADDR};
+use tmp117::{register::Average, Celsius};
+
+const DATA_READY: u16 = 1 << 13;
+
+#[test]
+fn capturing_observer_and_calibration() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY;
+    bus.registers[0x00] = 0x0C80;
+    let seen = Cell::new(0.0);
+    let gain = 2.0;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus)
+        .with_calibration(move |t| t * gain)
+        .with_read_observer(|t| seen.set(t));
+    assert_eq!(tmp.oneshot(Average::NoAverage), Ok(Celsius(50.0)));
+    // The observer receives the calibrated value
+    assert_eq!(seen.get(), 50.0);
+
+    let mut count = 0;
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus)
+        .with_read_observer(|_| count += 1);
+    block_on(tmp.oneshot(Average::NoAverage)).unwrap();
+    block_on(tmp.oneshot(Average::NoAverage)).unwrap();
+    assert_eq!(count, 2);
+}
+
+#[test]
+fn function_pointer_observer() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY;
+    bus.registers[0x00] = 0x0C80;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.set_calibration(|t| t + 1.0);
+    assert_eq!(tmp.oneshot(Average::NoAverage), Ok(Celsius(26.0)));
+    tmp.clear_calibration();
+    assert_eq!(tmp.oneshot(Average::NoAverage), Ok(Celsius(25.0)));
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use common::{block_on, MockI2c, MockPin, Wait, ADDR};
+use tmp117::{
+    asynchronous::PinWait,
+    register::{Average, Polarity},
+    ContinuousConfig,
+};
+
+const POLARITY: u16 = 1 << 3;
+const DATA_READY: u16 = 1 << 13;
+
+#[test]
+fn continuous_polarity_written() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    let config = ContinuousConfig {
+        polarity: Polarity::ActiveHigh,
+        ..Default::default()
+    };
+    tmp.continuous(config, |_| Ok(())).unwrap();
+    let writes = bus.writes(0x01);
+    assert_ne!(writes[0] & POLARITY, 0);
+}
+
+#[test]
+fn oneshot_polarity_written() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.oneshot(Average::NoAverage).unwrap();
+    tmp.set_oneshot_polarity(Polarity::ActiveHigh);
+    tmp.oneshot(Average::NoAverage).unwrap();
+    let writes = bus.writes(0x01);
+    assert_eq!(writes[0] & POLARITY, 0);
+    assert_ne!(writes[1] & POLARITY, 0);
+}
+
+#[test]
+fn async_waits_on_active_level() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY;
+    let mut pin = MockPin::default();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new_alert(&mut bus, &mut pin);
+
+    let config = ContinuousConfig {
+        polarity: Polarity::ActiveHigh,
+        ..Default::default()
+    };
+    block_on(tmp.continuous(config, |mut t| async move {
+        t.wait_temp().await?;
+        Ok(t)
+    }))
+    .unwrap();
+    block_on(tmp.oneshot(Average::NoAverage)).unwrap();
+
+    assert_eq!(pin.waits, [Wait::High, Wait::Low]);
+    let writes = bus.writes(0x01);
+    assert!(writes[1] & POLARITY != 0);
+}
+
+#[test]
+fn waits_follow_the_written_polarity() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY | (1 << 15);
+    let mut pin = MockPin::default();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new_alert(&mut bus, &mut pin);
+    let config = ContinuousConfig {
+        polarity: Polarity::ActiveHigh,
+        ..Default::default()
+    };
+    block_on(tmp.continuous(config, |mut t| async move {
+        t.wait_temp().await?;
+        t.wait_alert().await?;
+        Ok(t)
+    }))
+    .unwrap();
+    assert_eq!(pin.waits, [Wait::High, Wait::High]);
+
+    let mut pin = MockPin::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _, _>::new_with_alert(&mut bus, &mut pin);
+    tmp.continuous(config, |mut t| {
+        t.wait_temp()?;
+        t.wait_alert()?;
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(pin.waits, [Wait::High, Wait::High]);
+}
+
+#[test]
+fn edge_waits() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY;
+    let mut pin = MockPin::default();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new_alert(&mut bus, &mut pin);
+    tmp.set_pin_wait(PinWait::Edge);
+    block_on(tmp.oneshot(Average::NoAverage)).unwrap();
+    tmp.set_oneshot_polarity(Polarity::ActiveHigh);
+    block_on(tmp.oneshot(Average::NoAverage)).unwrap();
+    assert_eq!(pin.waits, [Wait::FallingEdge, Wait::RisingEdge]);
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use common::{block_on, MockI2c, Op, ADDR};
+use tmp117::Error;
+
+const DATA_READY: u16 = 1 << 13;
+
+#[test]
+fn read_temp_order() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.continuous(Default::default(), |mut t| {
+        t.read_temp()?;
+        t.wait_temp()?;
+        Ok(())
+    })
+    .unwrap();
+
+    let reads: Vec<_> = bus
+        .ops
+        .iter()
+        .filter(|op| matches!(op, Op::Read(_)))
+        .copied()
+        .collect();
+    assert_eq!(
+        reads,
+        [
+            // Continuous config edit
+            Op::Read(0x01),
+            // read_temp checks the flag before reading the temperature
+            Op::Read(0x01),
+            Op::Read(0x00),
+            // wait_temp too
+            Op::Read(0x01),
+            Op::Read(0x00),
+            // Shutdown config edit
+            Op::Read(0x01),
+        ]
+    );
+}
+
+#[test]
+fn read_temp_not_ready_async() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.continuous(Default::default(), |mut t| async move {
+        assert_eq!(t.read_temp().await, Err(Error::DataNotReady));
+        Ok(t)
+    }))
+    .unwrap();
+
+    // The temperature is never read when the data is not ready
+    assert!(!bus.ops.contains(&Op::Read(0x00)));
+}
+
+#[test]
+fn readings_iterator() {
+    let mut bus = MockI2c::new();
+    bus.registers[0x00] = 0x0C80;
+    bus.flags_on_read = DATA_READY;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.continuous(Default::default(), |t| {
+        for temp in t.readings().take(3) {
+            assert_eq!(temp?, tmp117::Celsius(25.0));
+        }
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(
+        bus.ops.iter().filter(|op| **op == Op::Read(0x00)).count(),
+        3
+    );
+}
+
+#[test]
+fn auto_shutdown_on_drop() {
+    let mut bus = MockI2c::new();
+    // Left in continuous mode by a previous firmware stage
+    bus.registers[0x01] = 0x0000;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    {
+        let mut tmp = tmp.with_auto_shutdown();
+        tmp.id().unwrap();
+    }
+    assert_eq!(bus.registers[0x01] & 0x0C00, 0x0400);
+}
+
+#[test]
+fn read_measurement() {
+    let mut bus = MockI2c::new();
+    bus.registers[0x00] = 0x0C80;
+    bus.flags_on_read = DATA_READY | (1 << 15);
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.continuous(Default::default(), |mut t| {
+        let m = t.read_measurement()?;
+        assert_eq!(m.celsius, tmp117::Celsius(25.0));
+        assert_eq!(m.alert, tmp117::Alert::High);
+        assert!(m.data_ready);
+        Ok(())
+    })
+    .unwrap();
+
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.continuous(Default::default(), |mut t| async {
+        let m = t.read_measurement().await?;
+        assert_eq!(m.alert, tmp117::Alert::None);
+        assert!(!m.data_ready);
+        Ok(t)
+    }))
+    .unwrap();
+}
+
+#[test]
+fn start_and_stop_continuous() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    let mut handler = tmp.start_continuous(Default::default()).unwrap();
+    handler.wait_temp().unwrap();
+    handler.stop().unwrap();
+    // Continuous then a single shutdown
+    assert_eq!(bus.writes(0x01).len(), 2);
+    assert_eq!(bus.registers[0x01] & 0x0C00, 0x0400);
+
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY;
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    let mut handler = block_on(tmp.start_continuous(Default::default())).unwrap();
+    block_on(handler.wait_temp()).unwrap();
+    block_on(handler.stop()).unwrap();
+    assert_eq!(bus.registers[0x01] & 0x0C00, 0x0400);
+}
+
+#[test]
+fn read_frame() {
+    use tmp117::MeasurementFrame;
+
+    let mut bus = MockI2c::new();
+    bus.registers[0x00] = 0xFF80;
+    bus.flags_on_read = DATA_READY | (1 << 14);
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.continuous(Default::default(), |mut t| {
+        let frame = t.read_frame()?;
+        assert_eq!(frame.raw_temp, -128);
+        assert_eq!(
+            frame.flags,
+            MeasurementFrame::FLAG_DATA_READY | MeasurementFrame::FLAG_LOW_ALERT
+        );
+        assert_eq!(frame.to_bytes(), [0xFF, 0x80, 0b101]);
+        assert_eq!(MeasurementFrame::from_bytes(frame.to_bytes()), frame);
+        Ok(())
+    })
+    .unwrap();
+}
@@ -0,0 +1,32 @@
+use tmp117::register::{
+    AlertPinSelect, Average, Configuration, Conversion, ConversionMode, Polarity, TriggerMode,
+};
+
+#[test]
+fn configuration_layout() {
+    // HIGH_Alert, Data_Ready, MOD = 11, CONV = 011, AVG = 10, T/nA, POL, DR/Alert
+    let config = Configuration::try_from(0b1010_1101_1101_1100u16).unwrap();
+    assert!(config.high_alert());
+    assert!(!config.low_alert());
+    assert!(config.data_ready());
+    assert!(!config.eeprom_busy());
+    assert_eq!(config.mode(), ConversionMode::OneShot);
+    assert_eq!(config.conversion(), Conversion::Ms500);
+    assert_eq!(config.average(), Average::Avg32);
+    assert_eq!(config.trigger_mode(), TriggerMode::Thermal);
+    assert_eq!(config.polarity(), Polarity::ActiveHigh);
+    assert_eq!(config.dr_alert(), AlertPinSelect::DataReady);
+    assert_eq!(u16::from(config), 0xADDC);
+}
+
+#[test]
+fn conversion_mode_is_two_bits() {
+    let mode = |bits: u16| Configuration::try_from(bits << 10).map(|c| c.mode());
+    assert_eq!(mode(0b00), Ok(ConversionMode::Continuous));
+    assert_eq!(mode(0b01), Ok(ConversionMode::Shutdown));
+    assert_eq!(mode(0b11), Ok(ConversionMode::OneShot));
+    // The bits above the mode belong to the flags
+    let config = Configuration::try_from(0b0001_1100_0000_0000u16).unwrap();
+    assert_eq!(config.mode(), ConversionMode::OneShot);
+    assert!(config.eeprom_busy());
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::{block_on, MockDelay, MockI2c, ADDR};
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+use tmp117::{Celsius, Error};
+
+const DATA_READY: u16 = 1 << 13;
+const NACK: Error<ErrorKind> = Error::Bus(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+
+#[test]
+fn read_temp_retries_bus_errors() {
+    let mut bus = MockI2c::new();
+    bus.registers[0x00] = 0x0C80;
+    bus.flags_on_read = DATA_READY;
+    // After the two transactions of the continuous config edit: the first reads of the configuration and temperature,
+    // then the two attempts of the configuration of the second read
+    bus.nacks = vec![2, 4, 6, 7];
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.continuous(Default::default(), |mut t| {
+        assert_eq!(t.read_temp_retry(1)?, Celsius(25.0));
+        assert_eq!(t.read_temp_retry(1), Err(NACK));
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn read_temp_retry_backoff_async() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY;
+    bus.nacks = vec![2, 3];
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.continuous(Default::default(), |mut t| async {
+        t.read_temp_retry_with_backoff(2, &mut delay, 100).await?;
+        Ok(t)
+    }))
+    .unwrap();
+    assert_eq!(delay.elapsed_ns, 200_000);
+}
+
+#[test]
+fn dropped_handler_keeps_shutdown_error() {
+    let mut bus = MockI2c::new();
+    // The shutdown when dropping the handler
+    bus.nacks = vec![2];
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    drop(tmp.start_continuous(Default::default()).unwrap());
+
+    // The next session only returns its own shutdown error
+    tmp.continuous(Default::default(), |_| Ok(())).unwrap();
+    assert_eq!(tmp.take_shutdown_error(), Some(NACK));
+    assert_eq!(tmp.take_shutdown_error(), None);
+}
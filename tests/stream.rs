@@ -0,0 +1,22 @@
+#![cfg(feature = "futures")]
+
+mod common;
+
+use common::{block_on, MockI2c, MockPin, Op, ADDR};
+use futures::StreamExt;
+use tmp117::{asynchronous::Tmp117, Celsius};
+
+#[test]
+fn temperature_stream() {
+    let mut bus = MockI2c::new();
+    bus.registers[0x00] = 0x0C80;
+    let mut pin = MockPin::default();
+    let mut tmp = Tmp117::<ADDR, _, _, _>::new_alert(&mut bus, &mut pin);
+    let handler = block_on(tmp.start_continuous(Default::default())).unwrap();
+    let temps: Vec<_> = block_on(handler.into_stream().take(3).collect());
+    assert_eq!(temps, [Ok(Celsius(25.0)); 3]);
+    assert_eq!(
+        bus.ops.iter().filter(|op| **op == Op::Read(0x00)).count(),
+        3
+    );
+}
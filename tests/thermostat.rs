@@ -0,0 +1,62 @@
+mod common;
+
+use common::{block_on, MockI2c, ADDR};
+use tmp117::{
+    asynchronous, thermostat::Thermostat, thermostat::ThermostatState, ContinuousConfig, Error,
+};
+
+const THERMAL: u16 = 1 << 4;
+const HIGH_ALERT: u16 = 1 << 15;
+
+#[test]
+fn thermostat_limits_and_state() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    let mut thermostat = Thermostat::new(&mut tmp, ContinuousConfig::default(), 22.0, 2.0).unwrap();
+    assert_eq!(thermostat.poll(), Ok(ThermostatState::Heating));
+    thermostat.stop().unwrap();
+
+    assert_eq!(bus.registers[0x02], 0x0B00);
+    assert_eq!(bus.registers[0x03], 0x0A00);
+    assert_ne!(bus.writes(0x01)[0] & THERMAL, 0);
+    assert_eq!(bus.registers[0x01] & 0x0C00, 0x0400);
+}
+
+#[test]
+fn thermostat_idle_async() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = HIGH_ALERT;
+    let mut tmp = asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    let mut thermostat = block_on(asynchronous::thermostat::Thermostat::new(
+        &mut tmp,
+        ContinuousConfig::default(),
+        22.0,
+        2.0,
+    ))
+    .unwrap();
+    assert_eq!(block_on(thermostat.poll()), Ok(ThermostatState::Idle));
+    block_on(thermostat.stop()).unwrap();
+}
+
+#[test]
+fn thermostat_negative_hysteresis() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert!(matches!(
+        Thermostat::new(&mut tmp, ContinuousConfig::default(), 22.0, -1.0),
+        Err(Error::InvalidLimits)
+    ));
+    assert!(bus.ops.is_empty());
+}
+
+#[test]
+fn poll_alert_without_handler() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = HIGH_ALERT;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(tmp.poll_alert(), Ok(tmp117::Alert::High));
+
+    let mut bus = MockI2c::new();
+    let mut tmp = asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    assert_eq!(block_on(tmp.poll_alert()), Ok(tmp117::Alert::None));
+}
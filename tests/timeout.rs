@@ -0,0 +1,235 @@
+mod common;
+
+use common::{block_on, MockDelay, MockI2c, Op, ADDR};
+use tmp117::{ContinuousConfig, Error};
+
+const DATA_READY: u16 = 1 << 13;
+
+#[test]
+fn try_wait_temp_times_out() {
+    let mut bus = MockI2c::new();
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    let res = tmp.continuous(ContinuousConfig::default(), |mut t| {
+        t.try_wait_temp(&mut delay, 5).map(|_| ())
+    });
+    assert_eq!(res, Err(Error::Timeout));
+    assert_eq!(delay.elapsed_ns, 5_000_000);
+}
+
+#[test]
+fn try_wait_temp_ready() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY;
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.continuous(ContinuousConfig::default(), |mut t| {
+        t.try_wait_temp(&mut delay, 5).map(|_| ())
+    })
+    .unwrap();
+    assert_eq!(delay.elapsed_ns, 0);
+}
+
+#[test]
+fn try_wait_temp_large_timeout() {
+    let mut bus = MockI2c::new();
+    bus.flags_on_read = DATA_READY;
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.continuous(ContinuousConfig::default(), |mut t| {
+        t.try_wait_temp(&mut delay, u32::MAX).map(|_| ())
+    })
+    .unwrap();
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(
+        tmp.continuous(ContinuousConfig::default(), |mut t| async move {
+            t.try_wait_temp(&mut delay, u32::MAX).await?;
+            Ok(t)
+        }),
+    )
+    .unwrap();
+}
+
+#[test]
+fn try_wait_alert_times_out_async() {
+    let mut bus = MockI2c::new();
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    let res = block_on(
+        tmp.continuous(ContinuousConfig::default(), |mut t| async move {
+            t.try_wait_alert(&mut delay, 1).await?;
+            Ok(t)
+        }),
+    );
+    assert_eq!(res, Err(Error::Timeout));
+}
+
+#[test]
+fn wait_eeprom_timeout() {
+    let mut bus = MockI2c::new();
+    // Stuck programming the EEPROM
+    bus.registers[0x04] |= 1 << 14;
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(
+        tmp.write_eeprom_timeout([1, 2, 3], &mut delay),
+        Err(Error::Timeout)
+    );
+    assert_eq!(
+        delay.elapsed_ns,
+        tmp117::EEPROM_WRITE_TIMEOUT_MS as u64 * 1_000_000
+    );
+    assert!(bus.writes(0x06).is_empty());
+
+    bus.registers[0x04] &= !(1 << 14);
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.write_eeprom_timeout([1, 2, 3], &mut delay)).unwrap();
+    assert_eq!(bus.writes(0x06), [2]);
+    assert_eq!(bus.writes(0x07), [3]);
+}
+
+#[test]
+fn wait_eeprom_large_timeout() {
+    let mut bus = MockI2c::new();
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.wait_eeprom_timeout(&mut delay, u32::MAX).unwrap();
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.wait_eeprom_timeout(&mut delay, u32::MAX)).unwrap();
+    assert_eq!(delay.elapsed_ns, 0);
+}
+
+#[test]
+fn wait_eeprom_zero_timeout() {
+    let mut bus = MockI2c::new();
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    tmp.wait_eeprom_timeout(&mut delay, 0).unwrap();
+
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.wait_eeprom_timeout(&mut delay, 0)).unwrap();
+    assert_eq!(bus.ops, [Op::Read(0x04), Op::Read(0x04)]);
+
+    // Busy, the single read fails right away
+    bus.registers[0x04] |= 1 << 14;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(tmp.wait_eeprom_timeout(&mut delay, 0), Err(Error::Timeout));
+    assert_eq!(delay.elapsed_ns, 0);
+}
+
+#[test]
+fn wait_first_conversion() {
+    let mut bus = MockI2c::new();
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert!(!tmp.is_first_conversion_done().unwrap());
+    assert_eq!(tmp.wait_first_conversion(&mut delay), Err(Error::Timeout));
+    assert_eq!(delay.elapsed_ns, 1_000_000_000);
+
+    bus.registers[0x00] = 0x0C80;
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    assert!(block_on(tmp.is_first_conversion_done()).unwrap());
+    block_on(tmp.wait_first_conversion(&mut delay)).unwrap();
+    assert_eq!(delay.elapsed_ns, 0);
+}
+
+#[test]
+fn read_temp_converted() {
+    let mut bus = MockI2c::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(tmp.read_temp_converted(), Err(Error::NotYetConverted));
+
+    // A genuine -256°C conversion
+    bus.flags_on_read = DATA_READY;
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    assert_eq!(
+        block_on(tmp.read_temp_converted()),
+        Ok(tmp117::Celsius(-256.0))
+    );
+}
+
+#[test]
+fn oneshot_with_delay() {
+    use common::Op;
+    use tmp117::register::Average;
+
+    let mut bus = MockI2c::new();
+    bus.registers[0x00] = 0x0C80;
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    assert_eq!(
+        tmp.oneshot_with_delay(Average::Avg8, &mut delay),
+        Ok(tmp117::Celsius(25.0))
+    );
+    assert_eq!(delay.elapsed_ns, 125_000_000);
+    // The configuration is never polled after starting the conversion
+    let start = bus
+        .ops
+        .iter()
+        .rposition(|op| matches!(op, Op::Write(0x01, _)))
+        .unwrap();
+    assert_eq!(bus.ops[start + 1..], [Op::Read(0x00)]);
+
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.oneshot_with_delay(Average::NoAverage, &mut delay)).unwrap();
+    assert_eq!(delay.elapsed_ns, 15_500_000);
+    // Shutdown after the read like oneshot
+    assert_eq!(bus.writes(0x01).last().unwrap() & 0x0C00, 0x0400);
+}
+
+#[test]
+fn oneshot_with_timeout_shuts_down() {
+    use tmp117::register::Average;
+
+    let mut bus = MockI2c::new();
+    let mut delay = MockDelay::default();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    assert_eq!(
+        block_on(tmp.oneshot_with_timeout(Average::NoAverage, &mut delay, 20)),
+        Err(Error::Timeout)
+    );
+    let config = bus.writes(0x01);
+    assert_eq!(config.len(), 2);
+    assert_eq!(config[1] & 0x0C00, 0x0400);
+
+    bus.flags_on_read = DATA_READY;
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    block_on(tmp.oneshot_with_timeout(Average::NoAverage, &mut delay, u32::MAX)).unwrap();
+    assert_eq!(bus.writes(0x01).last().unwrap() & 0x0C00, 0x0400);
+}
+
+#[test]
+fn wait_temp_with_progress_timeout() {
+    // The data ready flag is never set
+    let mut bus = MockI2c::new();
+    let mut delay = MockDelay::default();
+    let mut progress = Vec::new();
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    let res = tmp.continuous(ContinuousConfig::default(), |mut t| {
+        // An interval of 0 is polled every millisecond
+        t.wait_temp_with_progress(&mut delay, 0, |ms| progress.push(ms))?;
+        Ok(())
+    });
+    assert_eq!(res, Err(Error::Timeout));
+    assert_eq!(progress.first(), Some(&1));
+    assert_eq!(
+        delay.elapsed_ns,
+        *progress.last().unwrap() as u64 * 1_000_000
+    );
+
+    // The elapsed time saturates instead of overflowing
+    let mut delay = MockDelay::default();
+    let mut progress = Vec::new();
+    let mut tmp = tmp117::asynchronous::Tmp117::<ADDR, _, _, _>::new(&mut bus);
+    let res = block_on(tmp.continuous(ContinuousConfig::default(), |mut t| async {
+        t.wait_temp_with_progress(&mut delay, u32::MAX, |ms| progress.push(ms))
+            .await?;
+        Ok(t)
+    }));
+    assert_eq!(res.err(), Some(Error::Timeout));
+    assert_eq!(progress, [u32::MAX]);
+}
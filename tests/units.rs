@@ -0,0 +1,70 @@
+mod common;
+
+use common::{MockI2c, ADDR};
+use tmp117::register::Temperature;
+use tmp117::{
+    celsius_to_counts, counts_to_celsius, Celsius, CelsiusOutOfRange, FromRawTemperature,
+    CELCIUS_CONVERSION, TEMP_MAX_CELSIUS, TEMP_MAX_RAW, TEMP_MIN_CELSIUS, TEMP_MIN_RAW,
+};
+
+#[test]
+fn negative_full_scale() {
+    let temp = Temperature::from(0x8000);
+    assert_eq!(temp.as_celsius(), -256.0);
+    assert!((temp.as_fahrenheit() + 428.8).abs() < 1e-3);
+    assert!((temp.as_kelvin() - 17.15).abs() < 1e-3);
+}
+
+#[test]
+fn positive() {
+    // 25 °C
+    let temp = Temperature::from(0x0C80);
+    assert_eq!(temp.as_celsius(), 25.0);
+    assert_eq!(temp.as_fahrenheit(), 77.0);
+    assert!((temp.as_kelvin() - 298.15).abs() < 1e-3);
+}
+
+#[test]
+fn celsius_counts() {
+    assert_eq!(Celsius::from(-256 * 128), Celsius(-256.0));
+    assert_eq!(i16::try_from(Celsius(-256.0)), Ok(i16::MIN));
+    assert_eq!(i16::try_from(Celsius(255.99)), Ok(32766));
+    assert_eq!(i16::try_from(Celsius(300.0)), Err(CelsiusOutOfRange));
+    assert_eq!(i16::try_from(Celsius(f32::NAN)), Err(CelsiusOutOfRange));
+}
+
+#[test]
+fn range_constants() {
+    assert_eq!(TEMP_MIN_CELSIUS, -256.0);
+    assert_eq!(TEMP_MAX_CELSIUS, 256.0 - CELCIUS_CONVERSION);
+    assert_eq!(i16::try_from(Celsius(TEMP_MIN_CELSIUS)), Ok(TEMP_MIN_RAW));
+    assert_eq!(i16::try_from(Celsius(TEMP_MAX_CELSIUS)), Ok(TEMP_MAX_RAW));
+    assert_eq!(i16::try_from(Celsius(256.0)), Err(CelsiusOutOfRange));
+}
+
+#[test]
+fn from_raw_temperature() {
+    assert_eq!(f32::from_raw(0x0C80), 25.0);
+    assert_eq!(Celsius::from_raw(-1), Celsius(-0.0078125));
+}
+
+#[test]
+fn oneshot_as() {
+    let mut bus = MockI2c::new();
+    bus.registers[0x00] = 0x0C80;
+    bus.flags_on_read = 0x2000;
+    let mut tmp = tmp117::Tmp117::<ADDR, _, _>::new(&mut bus);
+    let temp: Celsius = tmp
+        .oneshot_as(tmp117::register::Average::NoAverage)
+        .unwrap();
+    assert_eq!(temp, Celsius(25.0));
+}
+
+#[test]
+fn counts_conversion() {
+    assert_eq!(counts_to_celsius(0x8000), -256.0);
+    assert_eq!(counts_to_celsius(0xFF80), -1.0);
+    assert_eq!(celsius_to_counts(-1.0), 0xFF80);
+    assert_eq!(celsius_to_counts(25.0), 0x0C80);
+    assert_eq!(celsius_to_counts(300.0), 0x7FFF);
+}